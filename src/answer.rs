@@ -0,0 +1,109 @@
+use std::fmt;
+
+use crate::Grid;
+
+/// The result of a puzzle part, whatever shape the puzzle asks for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    /// Any integer that fits in an `i128`.
+    Int(i128),
+    /// Unsigned integers too large for [`Answer::Int`]. `From<u128>` only produces this
+    /// when the value does not fit in an `i128`, so equal numbers always compare equal.
+    UInt(u128),
+    Text(String),
+    /// A multi-line rendering, e.g. letters drawn in ASCII art.
+    Grid(String),
+}
+
+impl Answer {
+    /// Short name of the variant, used when serializing results.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Int(_) | Answer::UInt(_) => "int",
+            Answer::Text(_) => "string",
+            Answer::Grid(_) => "grid",
+        }
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Grid(_))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::UInt(n) => write!(f, "{}", n),
+            Answer::Text(s) | Answer::Grid(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Int(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        match i128::try_from(n) {
+            Ok(n) => Answer::Int(n),
+            Err(_) => Answer::UInt(n),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl<T: fmt::Display> From<&Grid<T>> for Answer {
+    fn from(grid: &Grid<T>) -> Self {
+        Answer::Grid(grid.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_of_any_width_compare_equal() {
+        assert_eq!(Answer::from(42u8), Answer::from(42i64));
+        assert_eq!(Answer::from(42usize), Answer::from(42u128));
+        assert_eq!(Answer::from(-7i32).to_string(), "-7");
+
+        let huge = Answer::from(u128::MAX);
+        assert_eq!(huge, Answer::UInt(u128::MAX));
+        assert_eq!(huge.to_string(), u128::MAX.to_string());
+    }
+
+    #[test]
+    fn text_and_grid() {
+        assert_eq!(Answer::from("abc"), Answer::Text("abc".to_string()));
+
+        let grid = Grid::from_str("#.\n.#");
+        let answer = Answer::from(&grid);
+        assert!(answer.is_multiline());
+        assert_eq!(answer.kind(), "grid");
+        assert_eq!(answer.to_string(), "#.\n.#");
+    }
+}
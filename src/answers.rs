@@ -0,0 +1,151 @@
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::solution::Part;
+
+/// Known-good answers, one `answers/dayNN.json` file per day holding `{"part1": .., "part2": ..}`.
+///
+/// The answers to example `K` live next to it in `dayNN-K.json` instead, see [`AnswerStore::example`].
+#[derive(Debug, Clone)]
+pub struct AnswerStore {
+    dir: PathBuf,
+    suffix: String,
+}
+
+impl Default for AnswerStore {
+    fn default() -> Self {
+        AnswerStore::new("answers")
+    }
+}
+
+impl AnswerStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        AnswerStore {
+            dir: dir.into(),
+            suffix: String::new(),
+        }
+    }
+
+    pub fn example(dir: impl Into<PathBuf>, example: u32) -> Self {
+        AnswerStore {
+            dir: dir.into(),
+            suffix: format!("-{}", example),
+        }
+    }
+
+    fn path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{:02}{}.json", day, self.suffix))
+    }
+
+    fn key(part: Part) -> String {
+        format!("part{}", part.number())
+    }
+
+    fn load(&self, day: u8) -> Result<Map<String, Value>> {
+        let path = self.path(day);
+        if !path.exists() {
+            return Ok(Map::new());
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("could not read answers {}", path.display()))?;
+        match serde_json::from_str(&text) {
+            Ok(Value::Object(map)) => Ok(map),
+            _ => anyhow::bail!("answers {} must be a JSON object", path.display()),
+        }
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Result<Option<String>> {
+        Ok(self.load(day)?.get(&Self::key(part)).map(|v| match v {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }))
+    }
+
+    pub fn record(&self, day: u8, part: Part, answer: &str) -> Result<()> {
+        let mut answers = self.load(day)?;
+        answers.insert(Self::key(part), Value::String(answer.to_string()));
+
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("could not create {}", self.dir.display()))?;
+        let path = self.path(day);
+        let json = serde_json::to_string_pretty(&Value::Object(answers))?;
+        fs::write(&path, json + "\n").with_context(|| format!("could not write {}", path.display()))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Verdict {
+    pub fn new(expected: Option<String>, actual: &str) -> Self {
+        match expected {
+            Some(expected) if expected.trim() == actual.trim() => Verdict::Pass,
+            Some(expected) => Verdict::Fail { expected },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { .. } => write!(f, "FAIL"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn temp_store(name: &str) -> AnswerStore {
+        AnswerStore::new(temp_dir(name))
+    }
+
+    #[test]
+    fn record_and_get() {
+        let store = temp_store("answers");
+        assert_eq!(store.get(3, Part::One).unwrap(), None);
+
+        store.record(3, Part::One, "357").unwrap();
+        store.record(3, Part::Two, "3121910778619").unwrap();
+        assert_eq!(store.get(3, Part::One).unwrap(), Some("357".to_string()));
+        assert_eq!(
+            store.get(3, Part::Two).unwrap(),
+            Some("3121910778619".to_string())
+        );
+
+        let example = AnswerStore::example(store.dir(), 2);
+        assert_eq!(example.get(3, Part::One).unwrap(), None);
+        example.record(3, Part::One, "357").unwrap();
+        assert!(store.dir().join("day03-2.json").exists());
+
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+
+    #[test]
+    fn verdicts() {
+        assert_eq!(Verdict::new(Some("6".into()), "6"), Verdict::Pass);
+        assert_eq!(
+            Verdict::new(Some("6".into()), "7"),
+            Verdict::Fail {
+                expected: "6".into()
+            }
+        );
+        assert_eq!(Verdict::new(None, "7"), Verdict::Unknown);
+    }
+}
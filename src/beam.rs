@@ -0,0 +1,272 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+use crate::grid::{Direction, Grid, Point};
+
+/// Which way a splitter's two outgoing beams point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// `|`: beams travelling left or right leave up and down
+    Vertical,
+    /// `-`: beams travelling up or down leave left and right
+    Horizontal,
+}
+
+/// What a cell does to a beam that enters it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behaviour {
+    /// Carries on in the same direction
+    Pass,
+    /// Stops the beam
+    Absorb,
+    /// `/`, turning right-moving beams up
+    MirrorSlash,
+    /// `\`, turning right-moving beams down
+    MirrorBackslash,
+    /// Splits beams that cross the axis, lets beams along it pass
+    Split(Axis),
+    /// Sends a copy of the beam on from each cell beside it, in the same direction. The cells
+    /// beside it are energized but only pass the copies along, so their own rules do not apply
+    Fork,
+}
+
+/// A beam at a cell, travelling in a direction.
+pub type BeamState = (Point, Direction);
+
+/// What one cell did to a beam entering it.
+struct Step {
+    /// The beams leaving the cell
+    next: Vec<BeamState>,
+    /// Cells beside a fork that the copies went through without following their rules
+    passed: Vec<Point>,
+    /// Whether the cell split the beam
+    split: bool,
+}
+
+/// Traces beams through a char grid, looking up each cell's [`Behaviour`] by its character.
+///
+/// The default rules are `.` pass, `/` and `\` mirrors, and `|` and `-` splitters. Any other
+/// character needs a rule added with [`Beams::with`].
+#[derive(Debug, Clone)]
+pub struct Beams {
+    rules: HashMap<char, Behaviour>,
+}
+
+impl Default for Beams {
+    fn default() -> Self {
+        Beams {
+            rules: HashMap::from([
+                ('.', Behaviour::Pass),
+                ('/', Behaviour::MirrorSlash),
+                ('\\', Behaviour::MirrorBackslash),
+                ('|', Behaviour::Split(Axis::Vertical)),
+                ('-', Behaviour::Split(Axis::Horizontal)),
+            ]),
+        }
+    }
+}
+
+/// Everything that happened to the beams of one [`Beams::trace`].
+#[derive(Debug, Clone)]
+pub struct Trace {
+    /// Cells at least one beam went through
+    pub energized: Grid<bool>,
+    /// How many distinct splitter cells split a beam
+    pub splits: usize,
+    /// Where beams left the grid: the first point outside it and the direction of travel
+    pub exits: Vec<BeamState>,
+    /// Whether some beam came back to a cell and direction it had already been in
+    pub looped: bool,
+}
+
+impl Trace {
+    pub fn energized_count(&self) -> usize {
+        self.energized.count(|&e| e)
+    }
+}
+
+impl Beams {
+    pub fn new() -> Self {
+        Beams::default()
+    }
+
+    pub fn with(mut self, c: char, behaviour: Behaviour) -> Self {
+        self.rules.insert(c, behaviour);
+        self
+    }
+
+    /// Follows a beam entering `start` travelling `dir`, and every beam split off from it.
+    /// Each cell and direction is only followed once, so merging and looping beams terminate.
+    pub fn trace(&self, grid: &Grid<char>, start: Point, dir: Direction) -> Result<Trace> {
+        let mut trace = Trace {
+            energized: Grid::new(grid.width, grid.height, false),
+            splits: 0,
+            exits: vec![],
+            looped: false,
+        };
+        let mut splitters = HashSet::new();
+
+        // Depth first, so a successor still on the path means the beam has gone round a loop.
+        let mut on_path = HashSet::new();
+        let mut done = HashSet::new();
+        let mut path: Vec<(BeamState, Vec<BeamState>)> = vec![];
+        let mut entering = Some((start, dir));
+
+        loop {
+            if let Some(state) = entering.take() {
+                if !grid.in_bounds(state.0) {
+                    if done.insert(state) {
+                        trace.exits.push(state);
+                    }
+                } else if on_path.contains(&state) {
+                    trace.looped = true;
+                } else if !done.contains(&state) {
+                    let step = self.step(grid, state)?;
+                    if step.split {
+                        splitters.insert(state.0);
+                    }
+                    trace.energized.set(state.0, true);
+                    for p in step.passed {
+                        trace.energized.set(p, true);
+                    }
+                    on_path.insert(state);
+                    path.push((state, step.next));
+                }
+            }
+
+            let Some((current, next)) = path.last_mut() else {
+                break;
+            };
+            match next.pop() {
+                Some(state) => entering = Some(state),
+                None => {
+                    on_path.remove(current);
+                    done.insert(*current);
+                    path.pop();
+                }
+            }
+        }
+
+        trace.splits = splitters.len();
+        Ok(trace)
+    }
+
+    /// What the cell at `p` does to a beam entering it travelling `dir`.
+    fn step(&self, grid: &Grid<char>, (p, dir): BeamState) -> Result<Step> {
+        let c = *grid.get(p).unwrap();
+        let behaviour = self
+            .rules
+            .get(&c)
+            .ok_or_else(|| anyhow::anyhow!("no beam rule for {:?} at ({}, {})", c, p.x, p.y))?;
+
+        let ahead = |d: Direction| (p.step(d), d);
+        let to = |next: Vec<BeamState>| Step {
+            next,
+            passed: vec![],
+            split: false,
+        };
+        Ok(match behaviour {
+            Behaviour::Pass => to(vec![ahead(dir)]),
+            Behaviour::Absorb => to(vec![]),
+            Behaviour::MirrorSlash => to(vec![ahead(reflect_slash(dir))]),
+            Behaviour::MirrorBackslash => to(vec![ahead(reflect_slash(dir).opposite())]),
+            Behaviour::Split(axis) => match (axis, dir) {
+                (Axis::Vertical, Direction::Up | Direction::Down)
+                | (Axis::Horizontal, Direction::Left | Direction::Right) => to(vec![ahead(dir)]),
+                _ => Step {
+                    split: true,
+                    ..to(vec![ahead(dir.turn_left()), ahead(dir.turn_right())])
+                },
+            },
+            Behaviour::Fork => {
+                let sides = vec![p.step(dir.turn_left()), p.step(dir.turn_right())];
+                Step {
+                    next: sides.iter().map(|side| (side.step(dir), dir)).collect(),
+                    passed: sides,
+                    split: true,
+                }
+            }
+        })
+    }
+}
+
+fn reflect_slash(dir: Direction) -> Direction {
+    match dir {
+        Direction::Right => Direction::Up,
+        Direction::Up => Direction::Right,
+        Direction::Left => Direction::Down,
+        Direction::Down => Direction::Left,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRAPTION: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    #[test]
+    fn mirrors_and_splitters() {
+        let grid = Grid::from_str(CONTRAPTION);
+        let trace = Beams::new()
+            .trace(&grid, Point::new(0, 0), Direction::Right)
+            .unwrap();
+        assert_eq!(trace.energized_count(), 46);
+        assert!(trace.looped);
+
+        let trace = Beams::new()
+            .trace(&grid, Point::new(3, 0), Direction::Down)
+            .unwrap();
+        assert_eq!(trace.energized_count(), 51);
+    }
+
+    #[test]
+    fn loops_and_exits() {
+        let grid = Grid::from_str("/.\\\n...\n\\./");
+        let trace = Beams::new()
+            .trace(&grid, Point::new(1, 0), Direction::Right)
+            .unwrap();
+        assert!(trace.looped);
+        assert!(trace.exits.is_empty());
+        assert_eq!(trace.energized_count(), 8);
+
+        let trace = Beams::new()
+            .trace(&grid, Point::new(1, 1), Direction::Up)
+            .unwrap();
+        assert!(!trace.looped);
+        assert_eq!(trace.exits, vec![(Point::new(1, -1), Direction::Up)]);
+    }
+
+    #[test]
+    fn forks_absorbers_and_unknown_cells() {
+        let grid = Grid::from_str("..S..\n..^..\n.^#^.\n.....");
+        let beams = Beams::new()
+            .with('S', Behaviour::Pass)
+            .with('^', Behaviour::Fork)
+            .with('#', Behaviour::Absorb);
+        let trace = beams
+            .trace(&grid, Point::new(2, 0), Direction::Down)
+            .unwrap();
+        assert_eq!(trace.splits, 3);
+        // The start, the three forks, the five cells beside them and three in the bottom row.
+        assert_eq!(trace.energized_count(), 12);
+        assert!(!trace.looped);
+        let mut exits = trace.exits.iter().map(|(p, _)| p.x).collect::<Vec<_>>();
+        exits.sort();
+        assert_eq!(exits, vec![0, 2, 4]);
+
+        let err = Beams::new()
+            .trace(&grid, Point::new(2, 0), Direction::Down)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "no beam rule for 'S' at (2, 0)");
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::solution::Part;
+
+/// What a group of timing samples measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Parse,
+    Solve(Part),
+}
+
+impl Stage {
+    fn key(self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Solve(Part::One) => "part1",
+            Stage::Solve(Part::Two) => "part2",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "parse" => Some(Stage::Parse),
+            "part1" => Some(Stage::Solve(Part::One)),
+            "part2" => Some(Stage::Solve(Part::Two)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Solve(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+/// Summary statistics over a set of timing samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Stats {
+                min: Duration::ZERO,
+                median: Duration::ZERO,
+                mean: Duration::ZERO,
+                stddev: Duration::ZERO,
+            };
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        let median = match n % 2 {
+            0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+            _ => sorted[n / 2],
+        };
+
+        let secs = sorted.iter().map(|d| d.as_secs_f64()).collect::<Vec<_>>();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Timing statistics for one stage of one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub day: u8,
+    pub stage: Stage,
+    pub iterations: usize,
+    pub stats: Stats,
+}
+
+impl Measurement {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "day": self.day,
+            "stage": self.stage.key(),
+            "iterations": self.iterations,
+            "min_ns": self.stats.min.as_nanos() as u64,
+            "median_ns": self.stats.median.as_nanos() as u64,
+            "mean_ns": self.stats.mean.as_nanos() as u64,
+            "stddev_ns": self.stats.stddev.as_nanos() as u64,
+        })
+    }
+}
+
+pub fn to_json(measurements: &[Measurement]) -> Value {
+    json!({
+        "measurements": measurements.iter().map(Measurement::to_json).collect::<Vec<_>>(),
+    })
+}
+
+/// Median timings from a previously saved `bench --save` file.
+#[derive(Debug, Clone, Default)]
+pub struct Baseline {
+    medians: Vec<(u8, Stage, Duration)>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("could not read baseline {}", path.display()))?;
+        let value: Value = serde_json::from_str(&text)
+            .with_context(|| format!("baseline {} is not valid JSON", path.display()))?;
+        Self::from_json(&value).with_context(|| format!("malformed baseline {}", path.display()))
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let entries = value["measurements"]
+            .as_array()
+            .context("missing `measurements` array")?;
+
+        let medians = entries
+            .iter()
+            .map(|entry| {
+                let day = entry["day"].as_u64().context("missing `day`")? as u8;
+                let stage = entry["stage"]
+                    .as_str()
+                    .and_then(Stage::from_key)
+                    .context("missing or unknown `stage`")?;
+                let median = entry["median_ns"].as_u64().context("missing `median_ns`")?;
+                Ok((day, stage, Duration::from_nanos(median)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Baseline { medians })
+    }
+
+    pub fn median(&self, day: u8, stage: Stage) -> Option<Duration> {
+        self.medians
+            .iter()
+            .find(|&&(d, s, _)| d == day && s == stage)
+            .map(|&(_, _, median)| median)
+    }
+
+    /// Relative change of the median against the baseline, e.g. `0.25` for 25% slower.
+    pub fn change(&self, m: &Measurement) -> Option<f64> {
+        let base = self.median(m.day, m.stage)?.as_secs_f64();
+        if base == 0.0 {
+            return None;
+        }
+        Some(m.stats.median.as_secs_f64() / base - 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn stats_from_samples() {
+        let stats = Stats::from_samples(&[ms(4), ms(2), ms(6), ms(8)]);
+        assert_eq!(stats.min, ms(2));
+        assert_eq!(stats.median, ms(5));
+        assert_eq!(stats.mean, ms(5));
+        assert_eq!(stats.stddev.as_micros(), 2236);
+
+        let stats = Stats::from_samples(&[ms(3), ms(1), ms(2)]);
+        assert_eq!(stats.median, ms(2));
+    }
+
+    #[test]
+    fn baseline_round_trip() {
+        let m = Measurement {
+            day: 2,
+            stage: Stage::Solve(Part::Two),
+            iterations: 10,
+            stats: Stats::from_samples(&[ms(10)]),
+        };
+        let baseline = Baseline::from_json(&to_json(std::slice::from_ref(&m))).unwrap();
+        assert_eq!(baseline.median(2, Stage::Solve(Part::Two)), Some(ms(10)));
+        assert_eq!(baseline.median(2, Stage::Parse), None);
+
+        let slower = Measurement {
+            stats: Stats::from_samples(&[ms(15)]),
+            ..m
+        };
+        let change = baseline.change(&slower).unwrap();
+        assert!((change - 0.5).abs() < 1e-9);
+    }
+}
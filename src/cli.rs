@@ -1,24 +1,273 @@
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
 use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 use thiserror::Error;
 
-use crate::days::*;
+use crate::answer::Answer;
+use crate::answers::{AnswerStore, Verdict};
+use crate::bench::{self, Baseline, Measurement, Stage, Stats};
+use crate::days;
+use crate::days::day01::{self, Counting, Dial};
+use crate::examples::Examples;
+use crate::input::{self, InputProvider};
+use crate::scaffold;
+use crate::solution::{Part, Report, Samples};
+use crate::submit::{Outcome, SubmissionLog, Submitter};
+use crate::watch::Watcher;
 
-#[derive(Error, Debug)]
-enum ParseError {
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseError {
     #[error("Day not implemented: {0}")]
     NotImplemented(u8),
 
     #[error("No input file found: {0}")]
     NoInputFile(u8),
+
+    #[error("Day {day:02}, line {line}, column {column}: {message} (found {text:?})")]
+    Malformed {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+}
+
+impl ParseError {
+    pub fn malformed(
+        day: u8,
+        line: usize,
+        column: usize,
+        text: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        ParseError::Malformed {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Reports `text`, which must be a slice of `input`, at its 1-based line and column.
+    /// Slices that do not point into `input` are reported at line 0.
+    pub fn at(day: u8, input: &str, text: &str, message: impl Into<String>) -> Self {
+        let offset = (text.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+        if offset > input.len() {
+            return ParseError::malformed(day, 0, 0, text, message);
+        }
+
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = input[line_start..offset].chars().count() + 1;
+        ParseError::malformed(day, line, column, text, message)
+    }
+}
+
+/// Inclusive range of days, parsed from `5`, `3..=7` or `3..8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayRange {
+    start: u8,
+    end: u8,
+}
+
+impl DayRange {
+    pub fn days(self) -> impl Iterator<Item = u8> {
+        self.start..=self.end
+    }
+}
+
+impl FromStr for DayRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let parse_day = |d: &str| {
+            d.trim()
+                .parse::<u8>()
+                .map_err(|_| format!("invalid day: {:?}", d))
+        };
+
+        let (start, end) = if let Some((l, r)) = s.split_once("..=") {
+            (parse_day(l)?, parse_day(r)?)
+        } else if let Some((l, r)) = s.split_once("..") {
+            let end = parse_day(r)?
+                .checked_sub(1)
+                .ok_or_else(|| format!("empty day range: {:?}", s))?;
+            (parse_day(l)?, end)
+        } else {
+            let day = parse_day(s)?;
+            (day, day)
+        };
+
+        if start > end {
+            return Err(format!("empty day range: {:?}", s));
+        }
+        Ok(DayRange { start, end })
+    }
+}
+
+// Which days and parts to run. Shared by the runner and its subcommands.
+#[derive(clap::Args, Debug)]
+struct Selection {
+    /// Day to run, or a range of days such as `3..=7`
+    #[arg(short, long, default_value = "1", conflicts_with = "all")]
+    day: DayRange,
+
+    /// Run every implemented day
+    #[arg(short, long)]
+    all: bool,
+
+    /// Only run the given part (1 or 2)
+    #[arg(short, long)]
+    part: Option<Part>,
+}
+
+impl Selection {
+    fn days(&self) -> Vec<u8> {
+        if self.all {
+            days::SOLUTIONS.iter().map(|s| s.metadata().day).collect()
+        } else {
+            self.day.days().collect()
+        }
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct Args {
-    #[arg(short, long, default_value_t = 1)]
-    day: u8,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    selection: Selection,
+
+    /// Read the puzzle input from this file instead of `inputs/dayNN.txt` (downloaded when
+    /// missing and `AOC_SESSION` is set), `-` reads stdin
+    #[arg(short, long, conflicts_with = "all")]
+    input: Option<PathBuf>,
+
+    /// Run against `inputs/examples/dayNN-K.txt` instead of the puzzle input
+    #[arg(short, long, value_name = "K", conflicts_with = "input")]
+    example: Option<u32>,
+
+    /// How to print the results
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Compare answers against the ones recorded in `answers/`, or alongside the example with
+    /// `--example`
+    #[arg(long, conflicts_with_all = ["input", "format"])]
+    check: bool,
+
+    /// Like `--check`, but also record answers for parts that have none yet
+    #[arg(long, conflicts_with_all = ["input", "format"])]
+    record: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Time parsing and solving separately over repeated runs
+    Bench(BenchArgs),
+
+    /// Generate and register a new day module from the template
+    New {
+        day: u8,
+
+        /// Puzzle title recorded in the day's metadata
+        #[arg(short, long, default_value = "TODO")]
+        title: String,
+    },
+
+    /// Submit an answer, refusing values the submission log already rules out
+    Submit {
+        #[arg(short, long)]
+        day: u8,
+
+        #[arg(short, long)]
+        part: Part,
+
+        /// Submit this value instead of computing it from the puzzle input
+        #[arg(short, long)]
+        answer: Option<String>,
+    },
+
+    /// Run the day 1 dial with a different size, start, targets or way of counting
+    Dial {
+        /// Positions on the dial
+        #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(i64).range(1..))]
+        size: i64,
+
+        /// Position the dial starts at
+        #[arg(long, default_value_t = 50)]
+        start: i64,
+
+        /// Positions that count as hits, repeat for several
+        #[arg(short, long = "target", default_values_t = [0])]
+        targets: Vec<i64>,
+
+        /// Count rotations that end on a target, or every click onto one
+        #[arg(short, long, value_enum, default_value_t = Counting::PassThrough)]
+        counting: Counting,
+
+        /// Rotations to apply, one per line like `L68` or `L68x3`, instead of the day 1 input
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+
+    /// Re-run a day's examples and input whenever its module or input file changes
+    Watch {
+        day: u8,
+
+        /// How often to check for changes, in milliseconds
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+struct BenchArgs {
+    #[command(flatten)]
+    selection: Selection,
+
+    /// Number of timed runs per stage
+    #[arg(short = 'n', long, default_value_t = 10)]
+    iterations: usize,
+
+    /// Number of untimed runs before measuring
+    #[arg(short, long, default_value_t = 3)]
+    warmup: usize,
+
+    /// Save the measurements as JSON to this file
+    #[arg(long)]
+    save: Option<PathBuf>,
+
+    /// Compare medians against measurements saved with `--save`
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Slowdown of the median, in percent, that counts as a regression
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
 }
 
 pub fn parse_args() -> Result<Args> {
@@ -26,66 +275,466 @@ pub fn parse_args() -> Result<Args> {
 }
 
 fn read_input(day: u8) -> Result<String> {
-    match fs::read_to_string(format!("inputs/day{:02}.txt", day)) {
-        Ok(val) => Ok(val),
-        _ => anyhow::bail!(ParseError::NoInputFile(day)),
+    InputProvider::from_env().input(day)
+}
+
+fn read_input_from(path: &Path) -> Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .context("could not read input from stdin")?;
+        return Ok(input);
     }
+    fs::read_to_string(path).with_context(|| format!("could not read input {}", path.display()))
+}
+
+fn solve_day(day: u8, args: &Args) -> Result<Report> {
+    let solution = days::get(day).ok_or(ParseError::NotImplemented(day))?;
+    let input = match (&args.input, args.example) {
+        (Some(path), _) => read_input_from(path)?,
+        (None, Some(example)) => Examples::default().input(day, example)?,
+        (None, None) => read_input(day)?,
+    };
+    solution.solve(&input, &args.selection.parts())
 }
 
 pub fn run(args: Args) -> Result<()> {
-    let day = args.day;
+    match &args.command {
+        Some(Command::Bench(bench_args)) => return run_bench(bench_args),
+        Some(Command::New { day, title }) => return run_new(*day, title),
+        Some(Command::Submit { day, part, answer }) => {
+            return run_submit(*day, *part, answer.clone())
+        }
+        Some(Command::Dial {
+            size,
+            start,
+            targets,
+            counting,
+            input,
+        }) => {
+            let input = match input {
+                Some(path) => read_input_from(path)?,
+                None => read_input(1)?,
+            };
+            return run_dial(
+                Dial::with_size(*size, *start)
+                    .targets(targets.iter().copied())
+                    .counting(*counting),
+                &input,
+            );
+        }
+        Some(Command::Watch { day, interval }) => {
+            return Watcher {
+                day: *day,
+                interval: Duration::from_millis(*interval),
+            }
+            .run()
+        }
+        None => {}
+    }
+
+    let selected = args.selection.days();
+    match selected.as_slice() {
+        [_, _, ..] if args.input.is_some() => {
+            anyhow::bail!("--input can only be used with a single day")
+        }
+        _ if args.check || args.record => run_check(&selected, &args),
+        _ if args.format == Format::Json => run_json(&selected, &args),
+        [day] => run_single(*day, &args),
+        _ => run_summary(&selected, &args),
+    }
+}
+
+fn run_single(day: u8, args: &Args) -> Result<()> {
     println!();
     println!("Day {:02}", day);
     println!();
-    match day {
-        1 => {
-            let input = read_input(day)?;
-            println!("  Part one: {}", day01::part_one(&input));
-            println!("  Part two: {}", day01::part_two(&input));
-            Ok(())
-        }
-        2 => {
-            let input = read_input(day)?;
-            println!("  Part one: {}", day02::part_one(&input));
-            println!("  Part two: {}", day02::part_two(&input));
-            Ok(())
-        }
-        3 => {
-            let input = read_input(day)?;
-            println!("  Part one: {}", day03::part_one(&input));
-            println!("  Part two: {}", day03::part_two(&input));
-            Ok(())
-        }
-        4 => {
-            let input = read_input(day)?;
-            println!("  Part one: {}", day04::part_one(&input));
-            println!("  Part two: {}", day04::part_two(&input));
-            Ok(())
-        }
-        5 => {
-            let input = read_input(day)?;
-            println!("  Part one: {}", day05::part_one(&input));
-            println!("  Part two: {}", day05::part_two(&input));
-            Ok(())
-        }
-        6 => {
-            let input = read_input(day)?;
-            println!("  Part one: {}", day06::part_one(&input));
-            println!("  Part two: {}", day06::part_two(&input));
-            Ok(())
-        }
-        7 => {
-            let input = read_input(day)?;
-            println!("  Part one: {}", day07::part_one(&input));
-            println!("  Part two: {}", day07::part_two(&input));
-            Ok(())
-        }
-        8 => {
-            let input = read_input(day)?;
-            println!("  Part one: {}", day08::part_one(&input));
-            println!("  Part two: {}", day08::part_two(&input));
-            Ok(())
-        }
-        _ => anyhow::bail!(ParseError::NotImplemented(day)),
+
+    let report = solve_day(day, args)?;
+    for result in report.parts {
+        let label = match result.part {
+            Part::One => "Part one",
+            Part::Two => "Part two",
+        };
+        if result.answer.is_multiline() {
+            println!("  {}:", label);
+            for line in result.answer.to_string().lines() {
+                println!("    {}", line);
+            }
+        } else {
+            println!("  {}: {}", label, result.answer);
+        }
+    }
+    Ok(())
+}
+
+fn run_summary(selected: &[u8], args: &Args) -> Result<()> {
+    let mut rows = vec![];
+    let mut failures = vec![];
+    let mut total = Duration::ZERO;
+
+    for &day in selected {
+        match solve_day(day, args) {
+            Ok(report) => {
+                total += report.parse_elapsed;
+                for result in report.parts {
+                    total += result.elapsed;
+                    rows.push(vec![
+                        format!("{:02}", day),
+                        result.part.to_string(),
+                        table_cell(&result.answer),
+                        format!("{:.2?}", result.elapsed),
+                    ]);
+                }
+            }
+            Err(e) => failures.push((day, e)),
+        }
+    }
+
+    println!();
+    print_table(&["Day", "Part", "Answer", "Time"], &rows);
+    println!();
+    println!("  Total time: {:.2?}", total);
+
+    if !failures.is_empty() {
+        println!();
+        for (day, e) in failures {
+            println!("  Day {:02}: {:#}", day, e);
+        }
+    }
+    Ok(())
+}
+
+/// Prints one JSON object per part, or per day when the day could not run at all.
+fn run_json(selected: &[u8], args: &Args) -> Result<()> {
+    let results = selected
+        .iter()
+        .flat_map(|&day| match solve_day(day, args) {
+            Ok(report) => report
+                .parts
+                .into_iter()
+                .map(|result| {
+                    json!({
+                        "day": day,
+                        "part": result.part.number(),
+                        "answer": result.answer.to_string(),
+                        "type": result.answer.kind(),
+                        "duration_ns": result.elapsed.as_nanos() as u64,
+                        "error": null,
+                    })
+                })
+                .collect::<Vec<_>>(),
+            Err(e) => vec![json!({
+                "day": day,
+                "part": null,
+                "answer": null,
+                "type": null,
+                "duration_ns": null,
+                "error": format!("{:#}", e),
+            })],
+        })
+        .collect::<Vec<_>>();
+
+    println!("{}", serde_json::to_string_pretty(&results)?);
+    Ok(())
+}
+
+fn run_check(selected: &[u8], args: &Args) -> Result<()> {
+    let store = match args.example {
+        Some(example) => Examples::default().answers(example),
+        None => AnswerStore::default(),
+    };
+    let mut rows = vec![];
+    let mut failures = vec![];
+    let mut mismatches = 0;
+
+    for &day in selected {
+        let report = match solve_day(day, args) {
+            Ok(report) => report,
+            Err(e) => {
+                failures.push((day, e));
+                continue;
+            }
+        };
+
+        for result in report.parts {
+            let answer = result.answer.to_string();
+            let verdict = Verdict::new(store.get(day, result.part)?, &answer);
+            let (expected, status) = match verdict {
+                Verdict::Unknown if args.record => {
+                    store.record(day, result.part, &answer)?;
+                    (answer.clone(), "recorded".to_string())
+                }
+                Verdict::Pass => (answer.clone(), verdict.to_string()),
+                Verdict::Fail { ref expected } => {
+                    mismatches += 1;
+                    (expected.clone(), verdict.to_string())
+                }
+                Verdict::Unknown => ("-".to_string(), verdict.to_string()),
+            };
+            rows.push(vec![
+                format!("{:02}", day),
+                result.part.to_string(),
+                table_cell(&result.answer),
+                expected.replace('\n', " / "),
+                status,
+            ]);
+        }
+    }
+
+    println!();
+    print_table(&["Day", "Part", "Answer", "Expected", "Status"], &rows);
+
+    // A day with recorded answers that no longer runs is as much a regression as a mismatch.
+    let mut broken = 0;
+    if !failures.is_empty() {
+        println!();
+        for (day, e) in failures {
+            println!("  Day {:02}: {:#}", day, e);
+            for part in args.selection.parts() {
+                if store.get(day, part)?.is_some() {
+                    broken += 1;
+                    break;
+                }
+            }
+        }
+    }
+
+    match (mismatches, broken) {
+        (0, 0) => Ok(()),
+        (_, 0) => anyhow::bail!("{} answer(s) did not match", mismatches),
+        (0, _) => anyhow::bail!("{} day(s) with recorded answers failed to run", broken),
+        _ => anyhow::bail!(
+            "{} answer(s) did not match and {} day(s) with recorded answers failed to run",
+            mismatches,
+            broken
+        ),
+    }
+}
+
+fn run_dial(mut dial: Dial, input: &str) -> Result<()> {
+    let hits = dial.run(&day01::parse_rotations(input)?);
+    println!("  Hits: {}", hits);
+    println!("  Final position: {}", dial.pos);
+    Ok(())
+}
+
+fn run_new(day: u8, title: &str) -> Result<()> {
+    if !(1..=25).contains(&day) {
+        anyhow::bail!("day must be between 1 and 25, got {}", day);
+    }
+    for path in scaffold::create(Path::new("."), day, title)? {
+        println!("  {}", path.display());
+    }
+    Ok(())
+}
+
+fn run_submit(day: u8, part: Part, answer: Option<String>) -> Result<()> {
+    let session = input::session_from_env()
+        .context("no session cookie, set AOC_SESSION or write it to .aoc-session")?;
+
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let solution = days::get(day).ok_or(ParseError::NotImplemented(day))?;
+            let report = solution.solve(&read_input(day)?, &[part])?;
+            report.parts[0].answer.to_string()
+        }
+    };
+
+    let submitter = Submitter {
+        base_url: input::base_url_from_env(),
+        session,
+        log: SubmissionLog::default(),
+    };
+
+    println!("  Day {:02} part {}: submitting {}", day, part, answer);
+    let outcome = submitter.submit(day, part, &answer)?;
+    println!("  {}", outcome);
+
+    match outcome {
+        Outcome::Correct => AnswerStore::default().record(day, part, &answer),
+        _ => anyhow::bail!("answer was not accepted"),
+    }
+}
+
+fn bench_day(day: u8, args: &BenchArgs) -> Result<Samples> {
+    let solution = days::get(day).ok_or(ParseError::NotImplemented(day))?;
+    let input = read_input(day)?;
+    solution.bench(
+        &input,
+        &args.selection.parts(),
+        args.warmup,
+        args.iterations,
+    )
+}
+
+fn run_bench(args: &BenchArgs) -> Result<()> {
+    let baseline = args.baseline.as_deref().map(Baseline::load).transpose()?;
+
+    let mut measurements = vec![];
+    let mut failures = vec![];
+    for day in args.selection.days() {
+        match bench_day(day, args) {
+            Ok(samples) => {
+                let stages = [(Stage::Parse, samples.parse)].into_iter().chain(
+                    samples
+                        .parts
+                        .into_iter()
+                        .map(|(part, s)| (Stage::Solve(part), s)),
+                );
+                for (stage, s) in stages {
+                    measurements.push(Measurement {
+                        day,
+                        stage,
+                        iterations: s.len(),
+                        stats: Stats::from_samples(&s),
+                    });
+                }
+            }
+            Err(e) => failures.push((day, e)),
+        }
+    }
+
+    let mut headers = vec!["Day", "Stage", "Min", "Median", "Mean", "Stddev"];
+    if baseline.is_some() {
+        headers.push("Change");
+    }
+
+    let mut regressions = 0;
+    let rows = measurements
+        .iter()
+        .map(|m| {
+            let mut row = vec![
+                format!("{:02}", m.day),
+                m.stage.to_string(),
+                format!("{:.2?}", m.stats.min),
+                format!("{:.2?}", m.stats.median),
+                format!("{:.2?}", m.stats.mean),
+                format!("{:.2?}", m.stats.stddev),
+            ];
+            if let Some(baseline) = &baseline {
+                row.push(match baseline.change(m) {
+                    Some(change) if change * 100.0 > args.threshold => {
+                        regressions += 1;
+                        format!("{:+.1}% REGRESSION", change * 100.0)
+                    }
+                    Some(change) => format!("{:+.1}%", change * 100.0),
+                    None => "-".to_string(),
+                });
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+
+    println!();
+    print_table(&headers, &rows);
+
+    if !failures.is_empty() {
+        println!();
+        for (day, e) in failures {
+            println!("  Day {:02}: {:#}", day, e);
+        }
+    }
+
+    if let Some(path) = &args.save {
+        let json = serde_json::to_string_pretty(&bench::to_json(&measurements))?;
+        fs::write(path, json).with_context(|| format!("could not write {}", path.display()))?;
+    }
+
+    if regressions > 0 {
+        anyhow::bail!(
+            "{} stage(s) regressed more than {}%",
+            regressions,
+            args.threshold
+        );
+    }
+    Ok(())
+}
+
+/// Flattens multi-line answers so they fit in a single table row.
+fn table_cell(answer: &Answer) -> String {
+    answer.to_string().replace('\n', " / ")
+}
+
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let widths = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            rows.iter()
+                .map(|r| r[i].chars().count())
+                .chain([h.len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let render = |cells: Vec<&str>| {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, &w)| format!("{:<w$}", c, w = w))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        println!("  {}", line.trim_end());
+    };
+
+    render(headers.to_vec());
+    println!(
+        "  {}",
+        widths
+            .iter()
+            .map(|&w| "-".repeat(w))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    );
+    for row in rows {
+        render(row.iter().map(|c| c.as_str()).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn help_describes_the_runner() {
+        use clap::CommandFactory;
+
+        let command = Args::command();
+        command.clone().debug_assert();
+        let about = command
+            .get_about()
+            .map(|a| a.to_string())
+            .unwrap_or_default();
+        assert!(!about.contains("Which days"), "{}", about);
+    }
+
+    #[test]
+    fn parse_day_range() {
+        let days = |s: &str| s.parse::<DayRange>().map(|r| r.days().collect::<Vec<_>>());
+
+        assert_eq!(days("4"), Ok(vec![4]));
+        assert_eq!(days("3..=5"), Ok(vec![3, 4, 5]));
+        assert_eq!(days("3..5"), Ok(vec![3, 4]));
+        assert!(days("5..=3").is_err());
+        assert!(days("3..3").is_err());
+        assert!(days("x").is_err());
+    }
+
+    #[test]
+    fn parse_error_location() {
+        let input = "12\n3x4\n";
+        let text = &input[4..5];
+        assert_eq!(
+            ParseError::at(9, input, text, "bad"),
+            ParseError::malformed(9, 2, 2, "x", "bad")
+        );
+        assert_eq!(
+            ParseError::at(9, input, &input[input.len()..], "eof"),
+            ParseError::malformed(9, 3, 1, "", "eof")
+        );
     }
 }
@@ -1,96 +1,336 @@
-pub fn part_one(input: &str) -> i32 {
+use crate::answer::Answer;
+use crate::cli::ParseError;
+use crate::parse::parse_at;
+use crate::solution::{Metadata, Solution};
+use anyhow::Result;
+use clap::ValueEnum;
+
+const DAY: u8 = 1;
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Parsed = Vec<(Turn, i64, i64)>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: DAY,
+            title: "Secret Entrance",
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<(Turn, i64, i64)>> {
+        parse_rotations(input)
+    }
+
+    fn part_one(&self, rotations: &Vec<(Turn, i64, i64)>) -> Result<Answer> {
+        Ok(part_one(rotations)?.into())
+    }
+
+    fn part_two(&self, rotations: &Vec<(Turn, i64, i64)>) -> Result<Answer> {
+        Ok(part_two(rotations)?.into())
+    }
+}
+
+pub fn part_one(rotations: &[(Turn, i64, i64)]) -> Result<i64> {
+    let mut dial = Dial::new(50).counting(Counting::LandOn);
+    Ok(dial.run(rotations))
+}
+
+pub fn part_two(rotations: &[(Turn, i64, i64)]) -> Result<i64> {
     let mut dial = Dial::new(50);
+    Ok(dial.run(rotations))
+}
+
+/// Which way a rotation turns the dial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    /// `L`, towards lower numbers
+    Left,
+    /// `R`, towards higher numbers
+    Right,
+}
+
+/// Parses lines like `L68` into a [`Turn`], a click count and how many times to repeat it,
+/// which is 1 unless a trailing multiplier says otherwise, as in `L68x3`.
+pub fn parse_rotations(input: &str) -> Result<Vec<(Turn, i64, i64)>> {
+    // Counts are plain digits, so `R-5` cannot sneak in a left turn.
+    let count = |text: &str| match text.starts_with(|c: char| c.is_ascii_digit()) {
+        true => parse_at::<i64>(DAY, input, text),
+        false => Err(ParseError::at(DAY, input, text, "expected a number")),
+    };
+
     input
         .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
         .map(|line| {
-            match line.trim().split_at(1) {
-                ("L", by) => dial.rotate_left(by.parse().unwrap()),
-                ("R", by) => dial.rotate_right(by.parse().unwrap()),
-                _ => -1,
+            let dir = match line.chars().next() {
+                Some('L') => Turn::Left,
+                Some('R') => Turn::Right,
+                _ => {
+                    let message = "expected a rotation like `L68`";
+                    return Err(ParseError::at(DAY, input, line, message).into());
+                }
+            };
+            let (by, times) = match line[1..].split_once('x') {
+                Some((by, times)) => (count(by)?, count(times)?),
+                None => (count(&line[1..])?, 1),
             };
-            dial.pos
+            if by.checked_mul(times).is_none() {
+                let message = format!("expected at most {} clicks", i64::MAX);
+                return Err(ParseError::at(DAY, input, &line[1..], message).into());
+            }
+            Ok((dir, by, times))
         })
-        .filter(|&line| line == 0)
-        .count() as i32
+        .collect()
 }
 
-pub fn part_two(input: &str) -> i32 {
-    let mut dial = Dial::new(50);
-    input.lines().fold(0, |count, line| {
-        count
-            + match line.trim().split_at(1) {
-                ("L", by) => dial.rotate_left(by.parse().unwrap()),
-                ("R", by) => dial.rotate_right(by.parse().unwrap()),
-                _ => 0,
-            }
-    })
+/// What a [`Dial`] counts as a hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Counting {
+    /// Rotations that end on a target
+    LandOn,
+    /// Every click that lands on a target, including those in the middle of a rotation
+    PassThrough,
 }
 
-struct Dial {
-    pos: i32,
+/// A combination dial numbered `0..size` that counts how often it reaches its targets.
+///
+/// Defaults to the puzzle's dial: 100 positions, a single target at 0, counting every click
+/// onto it.
+#[derive(Debug, Clone)]
+pub struct Dial {
+    pub pos: i64,
+    pub size: i64,
+    pub targets: Vec<i64>,
+    pub counting: Counting,
 }
 
 impl Dial {
-    fn new(pos: i32) -> Self {
-        Dial { pos }
+    pub fn new(pos: i64) -> Self {
+        Dial::with_size(100, pos)
     }
 
-    fn rotate_left(&mut self, by: i32) -> i32 {
-        let mut rotations = 0;
-        for _ in 0..by {
-            self.pos -= 1;
-            if self.pos == -1 {
-                self.pos = 99
-            }
-            if self.pos == 0 {
-                rotations += 1;
-            }
+    /// A dial with `size` positions, starting at `pos`.
+    ///
+    /// Panics if `size` is not positive.
+    pub fn with_size(size: i64, pos: i64) -> Self {
+        assert!(
+            size > 0,
+            "a dial needs at least one position, got size {}",
+            size
+        );
+        Dial {
+            pos: pos.rem_euclid(size),
+            size,
+            targets: vec![0],
+            counting: Counting::PassThrough,
         }
+    }
+
+    pub fn targets(mut self, targets: impl IntoIterator<Item = i64>) -> Self {
+        self.targets = targets
+            .into_iter()
+            .map(|t| t.rem_euclid(self.size))
+            .collect();
+        self.targets.sort_unstable();
+        self.targets.dedup();
+        self
+    }
+
+    pub fn counting(mut self, counting: Counting) -> Self {
+        self.counting = counting;
+        self
+    }
+
+    /// Applies every rotation in turn, returning the total number of hits.
+    pub fn run(&mut self, rotations: &[(Turn, i64, i64)]) -> i64 {
         rotations
+            .iter()
+            .map(|&(dir, by, times)| self.turn(dir, by, times))
+            .sum()
     }
 
-    fn rotate_right(&mut self, by: i32) -> i32 {
-        let mut rotations = 0;
-        for _ in 0..by {
-            self.pos += 1;
-            if self.pos == 100 {
-                self.pos = 0
-            }
-            if self.pos == 0 {
-                rotations += 1;
+    /// Turns the dial `by` clicks, `times` times over, returning the hits it scored.
+    ///
+    /// Panics if `by * times` does not fit in an `i64`, which [`parse_rotations`] rules out.
+    pub fn turn(&mut self, dir: Turn, by: i64, times: i64) -> i64 {
+        let clicks = by
+            .checked_mul(times)
+            .unwrap_or_else(|| panic!("{} clicks {} times is too many", by, times));
+        match self.counting {
+            Counting::PassThrough => match dir {
+                Turn::Left => self.rotate_left(clicks),
+                Turn::Right => self.rotate_right(clicks),
+            },
+            Counting::LandOn => {
+                let step = match dir {
+                    Turn::Left => -(by % self.size),
+                    Turn::Right => by % self.size,
+                }
+                .rem_euclid(self.size);
+                let hits = self
+                    .targets
+                    .iter()
+                    .map(|&t| self.repeats_onto((t - self.pos).rem_euclid(self.size), step, times))
+                    .sum();
+                self.pos = match dir {
+                    Turn::Left => self.pos - clicks % self.size,
+                    Turn::Right => self.pos + clicks % self.size,
+                }
+                .rem_euclid(self.size);
+                hits
             }
         }
-        rotations
     }
+
+    /// Turns `by` clicks towards lower numbers, returning how many of them land on a target.
+    pub fn rotate_left(&mut self, by: i64) -> i64 {
+        let hits = self
+            .targets
+            .iter()
+            .map(|&t| self.clicks_onto((self.pos - t).rem_euclid(self.size), by))
+            .sum();
+        self.pos = (self.pos - by % self.size).rem_euclid(self.size);
+        hits
+    }
+
+    /// Turns `by` clicks towards higher numbers, returning how many of them land on a target.
+    pub fn rotate_right(&mut self, by: i64) -> i64 {
+        let hits = self
+            .targets
+            .iter()
+            .map(|&t| self.clicks_onto((t - self.pos).rem_euclid(self.size), by))
+            .sum();
+        self.pos = (self.pos + by % self.size).rem_euclid(self.size);
+        hits
+    }
+
+    /// How many of `by` clicks land on a target that is `distance` clicks away, where being on
+    /// it already means a full turn.
+    fn clicks_onto(&self, distance: i64, by: i64) -> i64 {
+        let first = match distance {
+            0 => self.size,
+            d => d,
+        };
+        match by < first {
+            true => 0,
+            false => (by - first) / self.size + 1,
+        }
+    }
+
+    /// How many of `times` repeats of `step` clicks upwards end on a target that is `distance`
+    /// clicks away.
+    fn repeats_onto(&self, distance: i64, step: i64, times: i64) -> i64 {
+        // Repeat `j` ends on the target when `j * step` is `distance` modulo the size. That needs
+        // the gcd of `step` and the size to divide `distance`, and then happens every
+        // `size / gcd` repeats, starting from `distance / gcd` times the inverse of `step / gcd`.
+        let (gcd, inverse) = gcd_and_inverse(step as i128, self.size as i128);
+        if distance as i128 % gcd != 0 {
+            return 0;
+        }
+        let period = self.size as i128 / gcd;
+        let first = match (distance as i128 / gcd * inverse).rem_euclid(period) {
+            0 => period,
+            j => j,
+        };
+        match (times as i128) < first {
+            true => 0,
+            false => ((times as i128 - first) / period + 1) as i64,
+        }
+    }
+}
+
+/// The gcd of `a` and `m`, and an `x` with `a * x` congruent to the gcd modulo `m`.
+fn gcd_and_inverse(a: i128, m: i128) -> (i128, i128) {
+    let (mut r, mut next_r) = (a, m);
+    let (mut x, mut next_x) = (1, 0);
+    while next_r != 0 {
+        let q = r / next_r;
+        (r, next_r) = (next_r, r - q * next_r);
+        (x, next_x) = (next_x, x - q * next_x);
+    }
+    (r, x)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "L68
-L30
-R48
-L5
-R60
-L55
-L1
-L99
-R14
-L82";
+    const INPUT: &str = include_str!("../../inputs/examples/day01-1.txt");
+
+    /// The original click-by-click dial, kept to check the arithmetic one against.
+    struct SteppedDial {
+        pos: i64,
+        size: i64,
+    }
+
+    impl SteppedDial {
+        fn rotate_left(&mut self, by: i64) -> i64 {
+            let mut rotations = 0;
+            for _ in 0..by {
+                self.pos -= 1;
+                if self.pos == -1 {
+                    self.pos = self.size - 1
+                }
+                if self.pos == 0 {
+                    rotations += 1;
+                }
+            }
+            rotations
+        }
+
+        fn rotate_right(&mut self, by: i64) -> i64 {
+            let mut rotations = 0;
+            for _ in 0..by {
+                self.pos += 1;
+                if self.pos == self.size {
+                    self.pos = 0
+                }
+                if self.pos == 0 {
+                    rotations += 1;
+                }
+            }
+            rotations
+        }
+    }
 
     #[test]
     fn test_part_one_example() {
-        let result = part_one(INPUT);
+        let result = part_one(&parse_rotations(INPUT).unwrap()).unwrap();
         assert_eq!(result, 3);
     }
 
     #[test]
     fn test_part_two_example() {
-        let result = part_two(INPUT);
+        let result = part_two(&parse_rotations(INPUT).unwrap()).unwrap();
         assert_eq!(result, 6);
     }
 
+    #[test]
+    fn test_malformed_input() {
+        let err = parse_rotations("L68\nX30\n").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 2, 1, "X30", "expected a rotation like `L68`")
+        );
+
+        let err = parse_rotations("L68\nR3o\n").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 2, 2, "3o", "expected a number")
+        );
+
+        for line in ["R-5", "L+5"] {
+            let err = parse_rotations(line).unwrap_err();
+            assert_eq!(
+                err.downcast::<ParseError>().unwrap(),
+                ParseError::malformed(DAY, 1, 2, &line[1..], "expected a number")
+            );
+        }
+    }
+
     #[test]
     fn test_example_step_by_step() {
         let mut dial = Dial::new(50);
@@ -179,4 +419,159 @@ L82";
         assert_eq!(rotations, 5);
         assert_eq!(dial.pos, 94);
     }
+
+    #[test]
+    fn test_matches_stepped_dial() {
+        for size in [1, 2, 3, 7, 100] {
+            for start in 0..size {
+                for by in 0..=3 * size + 1 {
+                    let mut dial = Dial::with_size(size, start);
+                    let mut oracle = SteppedDial { pos: start, size };
+                    assert_eq!(dial.rotate_left(by), oracle.rotate_left(by));
+                    assert_eq!(dial.pos, oracle.pos);
+
+                    let mut dial = Dial::with_size(size, start);
+                    let mut oracle = SteppedDial { pos: start, size };
+                    assert_eq!(dial.rotate_right(by), oracle.rotate_right(by));
+                    assert_eq!(dial.pos, oracle.pos);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_rotations() {
+        let mut dial = Dial::new(50);
+        assert_eq!(dial.rotate_right(1_000_000_000), 10_000_000);
+        assert_eq!(dial.pos, 50);
+        assert_eq!(dial.rotate_left(1_000_000_049), 10_000_000);
+        assert_eq!(dial.pos, 1);
+
+        let mut dial = Dial::with_size(7, 0);
+        assert_eq!(dial.rotate_left(i64::MAX / 2), i64::MAX / 2 / 7);
+
+        let mut dial = Dial::new(50);
+        assert_eq!(dial.rotate_right(i64::MAX), 92_233_720_368_547_758);
+        assert_eq!(dial.pos, 57);
+        assert_eq!(dial.rotate_left(i64::MAX), 92_233_720_368_547_758);
+        assert_eq!(dial.pos, 50);
+
+        let mut dial = Dial::with_size(i64::MAX, 1);
+        assert_eq!(dial.rotate_right(i64::MAX), 1);
+        assert_eq!(dial.pos, 1);
+    }
+
+    #[test]
+    fn test_targets_and_counting() {
+        let mut dial = Dial::with_size(10, 0).targets([3, 7, 13]);
+        assert_eq!(dial.targets, vec![3, 7]);
+        assert_eq!(dial.rotate_right(25), 5);
+        assert_eq!(dial.pos, 5);
+        assert_eq!(dial.rotate_left(2), 1);
+
+        let mut dial = Dial::with_size(10, 0)
+            .targets([3, 7])
+            .counting(Counting::LandOn);
+        assert_eq!(
+            dial.run(&[
+                (Turn::Right, 3, 1),
+                (Turn::Right, 2, 1),
+                (Turn::Left, 8, 1),
+                (Turn::Left, 20, 1)
+            ]),
+            3
+        );
+
+        for counting in [Counting::LandOn, Counting::PassThrough] {
+            let mut dial = Dial::new(50).counting(counting);
+            let mut oracle = SteppedDial { pos: 50, size: 100 };
+            let rotations = parse_rotations(INPUT).unwrap();
+            let hits = rotations
+                .iter()
+                .map(|&(dir, by, _)| {
+                    let clicks = match dir {
+                        Turn::Left => oracle.rotate_left(by),
+                        Turn::Right => oracle.rotate_right(by),
+                    };
+                    match counting {
+                        Counting::LandOn => (oracle.pos == 0) as i64,
+                        Counting::PassThrough => clicks,
+                    }
+                })
+                .sum::<i64>();
+            assert_eq!(dial.run(&rotations), hits);
+        }
+    }
+
+    #[test]
+    fn test_multipliers() {
+        assert_eq!(
+            parse_rotations("L68x3\nR5\n").unwrap(),
+            vec![(Turn::Left, 68, 3), (Turn::Right, 5, 1)]
+        );
+        let err = parse_rotations("L99999999999999999x9999").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(
+                DAY,
+                1,
+                2,
+                "99999999999999999x9999",
+                format!("expected at most {} clicks", i64::MAX)
+            )
+        );
+        let err = parse_rotations("R5xz").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 1, 4, "z", "expected a number")
+        );
+    }
+
+    #[test]
+    fn test_repeats_land_on_each_time() {
+        let repeated = parse_rotations("R100x3").unwrap();
+        let separate = parse_rotations("R100\nR100\nR100").unwrap();
+        for counting in [Counting::LandOn, Counting::PassThrough] {
+            let mut dial = Dial::with_size(100, 0).counting(counting);
+            let mut oracle = dial.clone();
+            assert_eq!(dial.run(&repeated), 3);
+            assert_eq!(oracle.run(&separate), 3);
+            assert_eq!(dial.pos, oracle.pos);
+        }
+
+        for size in [1, 2, 6, 10] {
+            for start in 0..size {
+                for by in 0..=2 * size {
+                    for times in 0..=2 * size {
+                        for dir in [Turn::Left, Turn::Right] {
+                            let mut dial = Dial::with_size(size, start)
+                                .targets([0, 3])
+                                .counting(Counting::LandOn);
+                            let mut oracle = dial.clone();
+                            let hits = (0..times)
+                                .map(|_| {
+                                    match dir {
+                                        Turn::Left => oracle.rotate_left(by),
+                                        Turn::Right => oracle.rotate_right(by),
+                                    };
+                                    oracle.targets.contains(&oracle.pos) as i64
+                                })
+                                .sum::<i64>();
+                            assert_eq!(dial.turn(dir, by, times), hits);
+                            assert_eq!(dial.pos, oracle.pos);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut dial = Dial::new(0).counting(Counting::LandOn);
+        assert_eq!(dial.turn(Turn::Left, 30, i64::MAX / 30), i64::MAX / 30 / 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "a dial needs at least one position, got size 0")]
+    fn test_empty_dial() {
+        Dial::with_size(0, 0);
+    }
 }
@@ -1,62 +1,198 @@
-use fancy_regex::Regex;
-use rayon::prelude::*;
+use crate::answer::Answer;
+use crate::parse::{self, comma_separated, range};
+use crate::solution::{Metadata, Solution};
+use anyhow::Result;
+use nom::error::context;
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
 
-pub fn part_one(input: &str) -> i64 {
-    process(input, false)
+const DAY: u8 = 2;
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Parsed = Vec<(u128, u128)>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: DAY,
+            title: "Gift Shop",
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<(u128, u128)>> {
+        parse_ranges(input)
+    }
+
+    fn part_one(&self, ranges: &Vec<(u128, u128)>) -> Result<Answer> {
+        Ok(part_one(ranges)?.into())
+    }
+
+    fn part_two(&self, ranges: &Vec<(u128, u128)>) -> Result<Answer> {
+        Ok(part_two(ranges)?.into())
+    }
 }
 
-pub fn part_two(input: &str) -> i64 {
-    process(input, true)
+pub fn part_one(ranges: &[(u128, u128)]) -> Result<u128> {
+    process(ranges, false)
 }
 
-fn process(input: &str, repeating: bool) -> i64 {
-    input
-        .trim()
-        .split(",")
-        .flat_map(|rng| {
-            let (start, end) = rng
-                .split_once("-")
-                .and_then(|(l, r)| Some((l.parse::<i64>().unwrap(), r.parse::<i64>().unwrap())))
-                .expect("could not parse number");
-            start..=end
+pub fn part_two(ranges: &[(u128, u128)]) -> Result<u128> {
+    process(ranges, true)
+}
+
+fn process(ranges: &[(u128, u128)], repeating: bool) -> Result<u128> {
+    ranges
+        .iter()
+        .try_fold(0u128, |sum, &(start, end)| {
+            sum.checked_add(repeated_id_sum(start, end, repeating)?)
         })
-        .collect::<Vec<i64>>()
-        .par_iter()
-        .filter(|num| is_repeating(&format!("{}", num), repeating))
-        .sum()
+        .ok_or_else(|| anyhow::anyhow!("sum of invalid IDs is more than {}", u128::MAX))
 }
 
-fn is_repeating(num: &str, more_than_twice: bool) -> bool {
+fn parse_ranges(input: &str) -> Result<Vec<(u128, u128)>> {
+    Ok(parse::run(
+        DAY,
+        input,
+        comma_separated(context("expected a range like `11-22`", range)),
+    )?)
+}
+
+/// Every number in `start..=end` made of one block of digits repeated, exactly twice or, with
+/// `more_than_twice`, any number of times.
+///
+/// A block `B` of `b` digits repeated `k` times is `B * (10^(b(k-1)) + ... + 10^b + 1)`, so for
+/// each length `b * k` the matching blocks form a contiguous range found by division. Numbers
+/// like `222222` repeat several ways and are only counted once.
+pub fn repeated_ids(start: u128, end: u128, more_than_twice: bool) -> BTreeSet<u128> {
+    let mut ids = BTreeSet::new();
+    for len in digits(start)..=digits(end) {
+        for k in repeat_counts(len, more_than_twice) {
+            if let Some((multiplier, blocks)) = blocks(start, end, len, k) {
+                ids.extend(blocks.map(|b| b * multiplier));
+            }
+        }
+    }
+    ids
+}
+
+/// The sum of [`repeated_ids`] without listing them, or `None` if it does not fit in a `u128`.
+pub fn repeated_id_sum(start: u128, end: u128, more_than_twice: bool) -> Option<u128> {
+    (digits(start)..=digits(end)).try_fold(0u128, |sum, len| {
+        let counts = repeat_counts(len, more_than_twice);
+        sum.checked_add(union_sum(start, end, len, &counts)?)
+    })
+}
+
+/// The repeat counts whose numbers cover every match of length `len`. A block repeated `k`
+/// times is also repeated `p` times for every prime `p` dividing `k`, so primes are enough.
+fn repeat_counts(len: u32, more_than_twice: bool) -> Vec<u32> {
     match more_than_twice {
-        true => Regex::new(r"^(\d+?)\1+$")
-            .unwrap()
-            .is_match(num)
-            .unwrap_or(false),
-        false => Regex::new(r"^(\d+)\1$")
-            .unwrap()
-            .is_match(num)
-            .unwrap_or(false),
+        true => (2..=len)
+            .filter(|&p| len.is_multiple_of(p) && (2..p).all(|d| !p.is_multiple_of(d)))
+            .collect(),
+        false if len.is_multiple_of(2) => vec![2],
+        false => vec![],
+    }
+}
+
+/// Sum of the `len` digit numbers in `start..=end` that repeat a block `k` times for some `k`
+/// in `counts`, by inclusion–exclusion: numbers repeating both `k` and `r` times repeat
+/// `lcm(k, r)` times. Every partial sum is part of the final one, so `None` only ever means
+/// the result itself overflows.
+fn union_sum(start: u128, end: u128, len: u32, counts: &[u32]) -> Option<u128> {
+    let Some((&k, rest)) = counts.split_first() else {
+        return Some(0);
+    };
+    let overlaps = rest.iter().map(|&r| lcm(k, r)).collect::<Vec<_>>();
+    let only_rest = union_sum(start, end, len, rest)? - union_sum(start, end, len, &overlaps)?;
+    repetition_sum(start, end, len, k)?.checked_add(only_rest)
+}
+
+/// Sum of the `len` digit numbers in `start..=end` made of a block repeated `k` times.
+fn repetition_sum(start: u128, end: u128, len: u32, k: u32) -> Option<u128> {
+    let Some((multiplier, blocks)) = blocks(start, end, len, k) else {
+        return Some(0);
+    };
+    let (lo, hi) = blocks.into_inner();
+    let count = hi - lo + 1;
+    let block_sum = match count % 2 {
+        0 => (count / 2).checked_mul(lo + hi)?,
+        _ => count.checked_mul((lo + hi) / 2)?,
+    };
+    multiplier.checked_mul(block_sum)
+}
+
+/// The multiplier for a block repeated `k` times in a `len` digit number, and the blocks that
+/// land in `start..=end`. `None` when there are no such blocks.
+fn blocks(start: u128, end: u128, len: u32, k: u32) -> Option<(u128, RangeInclusive<u128>)> {
+    let block = len / k;
+    let multiplier = repetition_multiplier(block, k)?;
+    let smallest = 10u128.pow(block - 1).max(start.div_ceil(multiplier));
+    let largest = (10u128.pow(block) - 1).min(end / multiplier);
+    (smallest <= largest).then_some((multiplier, smallest..=largest))
+}
+
+/// `1` followed by `k - 1` copies of `block - 1` zeros and a `1`, e.g. 10101 for 2 and 3,
+/// or `None` if it does not fit in a `u128`.
+fn repetition_multiplier(block: u32, k: u32) -> Option<u128> {
+    let shift = 10u128.checked_pow(block)?;
+    (1..k).try_fold(1u128, |m, _| m.checked_mul(shift)?.checked_add(1))
+}
+
+fn digits(n: u128) -> u32 {
+    n.checked_ilog10().unwrap_or(0) + 1
+}
+
+fn lcm(a: u32, b: u32) -> u32 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
     }
+    a / x * b
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::ParseError;
+    use fancy_regex::Regex;
+    use std::sync::LazyLock;
 
-    const INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
+    static TWICE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\d+)\1$").unwrap());
+    static REPEATED: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\d+?)\1+$").unwrap());
+
+    /// The original regex check, kept to test the arithmetic enumeration against.
+    fn is_repeating(num: &str, more_than_twice: bool) -> bool {
+        match more_than_twice {
+            true => REPEATED.is_match(num).unwrap_or(false),
+            false => TWICE.is_match(num).unwrap_or(false),
+        }
+    }
+
+    const INPUT: &str = include_str!("../../inputs/examples/day02-1.txt");
 
     #[test]
     fn test_part_one_example() {
-        let result = part_one(INPUT);
+        let result = part_one(&parse_ranges(INPUT).unwrap()).unwrap();
         assert_eq!(result, 1227775554);
     }
 
     #[test]
     fn test_part_two_example() {
-        let result = part_two(INPUT);
+        let result = part_two(&parse_ranges(INPUT).unwrap()).unwrap();
         assert_eq!(result, 4174379265);
     }
 
+    #[test]
+    fn test_malformed_input() {
+        let err = parse_ranges("11-22,95115").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 1, 7, "95115", "expected a range like `11-22`")
+        );
+    }
+
     #[test]
     fn test_is_repeating() {
         assert!(is_repeating("1010", true));
@@ -72,4 +208,68 @@ mod tests {
         assert!(is_repeating("824824824", true));
         assert!(is_repeating("99", true));
     }
+
+    #[test]
+    fn test_matches_regex() {
+        for (start, end) in [
+            (1, 1200),
+            (95, 115),
+            (99_990, 101_100),
+            (1_698_000, 1_699_000),
+        ] {
+            for more_than_twice in [false, true] {
+                let expected = (start..=end)
+                    .filter(|n: &u128| is_repeating(&n.to_string(), more_than_twice))
+                    .collect::<BTreeSet<_>>();
+                assert_eq!(repeated_ids(start, end, more_than_twice), expected);
+                assert_eq!(
+                    repeated_id_sum(start, end, more_than_twice),
+                    Some(expected.iter().sum())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_wide_ranges() {
+        let start = 10u128.pow(19);
+        assert_eq!(
+            repeated_ids(start, start + 10u128.pow(10), false),
+            BTreeSet::from([10_000_000_001_000_000_000])
+        );
+
+        let top = repeated_ids(u128::MAX - 10u128.pow(25), u128::MAX, true);
+        assert!(!top.is_empty());
+        assert!(top.iter().all(|n| is_repeating(&n.to_string(), true)));
+
+        let checked_sum =
+            |ids: &BTreeSet<u128>| ids.iter().try_fold(0u128, |sum, &id| sum.checked_add(id));
+        assert_eq!(
+            repeated_id_sum(u128::MAX - 10u128.pow(25), u128::MAX, true),
+            checked_sum(&top)
+        );
+
+        // Thirty ones repeat in blocks of 1, 2, 3, 5, 6, 10 and 15, but count once.
+        let ones = "1".repeat(30).parse::<u128>().unwrap();
+        let near = repeated_ids(ones - 10u128.pow(12), ones + 10u128.pow(12), true);
+        assert!(near.contains(&ones));
+        assert_eq!(
+            repeated_id_sum(ones - 10u128.pow(12), ones + 10u128.pow(12), true),
+            checked_sum(&near)
+        );
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        let ranges = parse_ranges(
+            "200000000000020000000000002000000000000-200000000000120000000000012000000000001",
+        )
+        .unwrap();
+        assert_eq!(part_one(&ranges).unwrap(), 0);
+        let err = part_two(&ranges).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("sum of invalid IDs is more than {}", u128::MAX)
+        );
+    }
 }
@@ -1,83 +1,228 @@
-use itertools::Itertools;
+use crate::answer::Answer;
+use crate::cli::ParseError;
+use crate::solution::{Metadata, Solution};
+use anyhow::Result;
+use std::fmt;
 
-fn find_largest(bank: &[i64], start: usize, stop: usize) -> (usize, i64) {
-    let (i, v) = &bank[start..stop]
-        .iter()
-        .enumerate()
-        .sorted_by_key(|v| -v.1)
-        .take(1)
-        .next()
-        .unwrap();
-    (*i, **v)
+const DAY: u8 = 3;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Parsed = Vec<Vec<u8>>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: DAY,
+            title: "Lobby",
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<u8>>> {
+        parse_banks(input)
+    }
+
+    fn part_one(&self, banks: &Vec<Vec<u8>>) -> Result<Answer> {
+        Ok(part_one(banks)?.into())
+    }
+
+    fn part_two(&self, banks: &Vec<Vec<u8>>) -> Result<Answer> {
+        Ok(part_two(banks)?.into())
+    }
 }
 
-pub fn part_one(input: &str) -> i64 {
-    input
-        .trim()
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap() as i64)
-                .collect::<Vec<i64>>()
-        })
-        .map(|bank| {
-            let (idx, first_num) = find_largest(&bank, 0, bank.len() - 1);
-            let (_, second_num) = find_largest(&bank, idx + 1, bank.len());
-            first_num * 10 + second_num
+pub fn part_one(banks: &[Vec<u8>]) -> Result<u128> {
+    total_joltage(banks, 2)
+}
+
+pub fn part_two(banks: &[Vec<u8>]) -> Result<u128> {
+    total_joltage(banks, 12)
+}
+
+/// Sums the largest `k` digit joltage of every bank, each of which needs at least `k` digits.
+fn total_joltage(banks: &[Vec<u8>], k: usize) -> Result<u128> {
+    banks
+        .iter()
+        .enumerate()
+        .map(|(i, bank)| {
+            if bank.len() < k {
+                let text = bank.iter().map(|d| d.to_string()).collect::<String>();
+                let message = format!("expected a bank of at least {} batteries", k);
+                return Err(ParseError::malformed(DAY, i + 1, 1, &text, message).into());
+            }
+            Ok(to_u128(&max_subsequence_digits(bank, k)))
         })
         .sum()
 }
 
-fn process_bank(bank: &[i64]) -> i64 {
-    let mut new_bank = vec![];
-    let mut last_index = 0;
-    for i in 0..12 {
-        let start = i + last_index;
-        let stop = bank.len() - 11 + i;
-        let (index, largest) = find_largest(bank, start, stop);
-        last_index = index + last_index;
-        new_bank.push(largest);
+/// A number picked out of a bank's digits, held as digits once it is too long for a `u128`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subsequence {
+    Int(u128),
+    Digits(Vec<u8>),
+}
+
+impl From<Vec<u8>> for Subsequence {
+    fn from(digits: Vec<u8>) -> Self {
+        match digits.len() {
+            0..=38 => Subsequence::Int(to_u128(&digits)),
+            _ => Subsequence::Digits(digits),
+        }
     }
-    new_bank
-        .iter()
-        .map(|&x| x.to_string())
-        .collect::<Vec<_>>()
-        .concat()
-        .parse()
-        .unwrap()
 }
 
-pub fn part_two(input: &str) -> i64 {
+impl fmt::Display for Subsequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Subsequence::Int(n) => write!(f, "{}", n),
+            Subsequence::Digits(digits) => digits.iter().try_for_each(|d| write!(f, "{}", d)),
+        }
+    }
+}
+
+/// The largest number made of `k` of the bank's digits, kept in order.
+pub fn max_subsequence_number(bank: &[u8], k: usize) -> Subsequence {
+    max_subsequence_digits(bank, k).into()
+}
+
+/// The smallest number made of `k` of the bank's digits, kept in order.
+pub fn min_subsequence_number(bank: &[u8], k: usize) -> Subsequence {
+    min_subsequence_digits(bank, k).into()
+}
+
+pub fn max_subsequence_digits(bank: &[u8], k: usize) -> Vec<u8> {
+    select_digits(bank, k, |kept, next| kept < next)
+}
+
+pub fn min_subsequence_digits(bank: &[u8], k: usize) -> Vec<u8> {
+    select_digits(bank, k, |kept, next| kept > next)
+}
+
+/// Picks `k` digits in linear time with a monotonic stack: each digit evicts the kept digits
+/// `next` beats, as long as enough of the bank remains to still fill `k` places. Banks shorter
+/// than `k` are returned whole.
+fn select_digits(bank: &[u8], k: usize, beats: impl Fn(u8, u8) -> bool) -> Vec<u8> {
+    let mut droppable = bank.len().saturating_sub(k);
+    let mut kept = Vec::with_capacity(bank.len());
+    for &digit in bank {
+        while droppable > 0 && kept.last().is_some_and(|&last| beats(last, digit)) {
+            kept.pop();
+            droppable -= 1;
+        }
+        kept.push(digit);
+    }
+    kept.truncate(k);
+    kept
+}
+
+fn to_u128(digits: &[u8]) -> u128 {
+    digits.iter().fold(0, |acc, &d| acc * 10 + d as u128)
+}
+
+/// Parses one bank of battery joltages per line.
+fn parse_banks(input: &str) -> Result<Vec<Vec<u8>>> {
     input
-        .trim()
+        .trim_end()
         .lines()
         .map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap() as i64)
-                .collect::<Vec<i64>>()
+            line.char_indices()
+                .map(|(i, c)| {
+                    c.to_digit(10).map(|d| d as u8).ok_or_else(|| {
+                        ParseError::at(DAY, input, &line[i..i + c.len_utf8()], "expected a digit")
+                            .into()
+                    })
+                })
+                .collect()
         })
-        .map(|bank| process_bank(&bank))
-        .sum()
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use itertools::Itertools;
 
-    const INPUT: &str = "987654321111111
-811111111111119
-234234234234278
-818181911112111";
+    const INPUT: &str = include_str!("../../inputs/examples/day03-1.txt");
 
     #[test]
     fn test_part_one_example() {
-        let result = part_one(INPUT);
+        let result = part_one(&parse_banks(INPUT).unwrap()).unwrap();
         assert_eq!(result, 357);
     }
 
     #[test]
     fn test_part_two_example() {
-        let result = part_two(INPUT);
+        let result = part_two(&parse_banks(INPUT).unwrap()).unwrap();
         assert_eq!(result, 3121910778619);
     }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = parse_banks("987\n8x1\n").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 2, 2, "x", "expected a digit")
+        );
+
+        let banks = parse_banks("987654321111111\n81119\n").unwrap();
+        let err = part_two(&banks).unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(
+                DAY,
+                2,
+                1,
+                "81119",
+                "expected a bank of at least 12 batteries"
+            )
+        );
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let banks = [
+            "987654321111111",
+            "818181911112111",
+            "1020304050",
+            "5555",
+            "9",
+        ];
+        for bank in banks {
+            let bank = bank.bytes().map(|b| b - b'0').collect::<Vec<_>>();
+            for k in 1..=bank.len() {
+                let picks = bank
+                    .iter()
+                    .copied()
+                    .combinations(k)
+                    .map(|digits| to_u128(&digits))
+                    .collect::<Vec<_>>();
+                let max = max_subsequence_number(&bank, k);
+                let min = min_subsequence_number(&bank, k);
+                assert_eq!(max, Subsequence::Int(*picks.iter().max().unwrap()));
+                assert_eq!(min, Subsequence::Int(*picks.iter().min().unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_selections() {
+        let bank = "12"
+            .repeat(30)
+            .bytes()
+            .map(|b| b - b'0')
+            .collect::<Vec<_>>();
+        assert_eq!(
+            max_subsequence_number(&bank, 38),
+            Subsequence::Int(("2".repeat(22) + &"12".repeat(8)).parse().unwrap())
+        );
+
+        let max = max_subsequence_number(&bank, 40);
+        assert_eq!(max.to_string(), "2".repeat(20) + &"12".repeat(10));
+        assert!(matches!(max, Subsequence::Digits(ref d) if d.len() == 40));
+        assert_eq!(
+            min_subsequence_number(&bank, 40).to_string(),
+            "1".repeat(21) + &"21".repeat(9) + "2"
+        );
+        assert_eq!(max_subsequence_number(&bank[..3], 5).to_string(), "121");
+    }
 }
@@ -1,79 +1,154 @@
-use crate::{Grid, Point};
-
-pub fn part_one(input: &str) -> usize {
-    let grid = Grid::from_str(input);
-    all_tp_locations(&grid)
-        .iter()
-        .filter(|&&p| tp_neighbor_count_less_than_four(&grid, p))
-        .count()
-}
+use crate::answer::Answer;
+use crate::parse::{self, char_grid};
+use crate::solution::{Metadata, Solution};
+use crate::Grid;
+use anyhow::Result;
+
+const DAY: u8 = 4;
 
-pub fn part_two(input: &str) -> usize {
-    let mut grid = Grid::from_str(input);
-    let mut removed = 0;
+pub struct Day04;
 
-    loop {
-        let candidates = all_tp_locations(&grid)
-            .into_iter()
-            .filter(|&p| tp_neighbor_count_less_than_four(&grid, p))
-            .collect::<Vec<_>>();
+impl Solution for Day04 {
+    type Parsed = Grid<char>;
 
-        match candidates.len() {
-            0 => break,
-            _ => removed += candidates.len(),
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: DAY,
+            title: "Printing Department",
         }
+    }
 
-        remove_tp_at_locations(&mut grid, candidates);
+    fn parse(&self, input: &str) -> Result<Grid<char>> {
+        parse_rolls(input)
     }
 
-    removed
+    fn part_one(&self, grid: &Grid<char>) -> Result<Answer> {
+        Ok(part_one(grid)?.into())
+    }
+
+    fn part_two(&self, grid: &Grid<char>) -> Result<Answer> {
+        Ok(part_two(grid)?.into())
+    }
 }
 
-fn remove_tp_at_locations(grid: &mut Grid<char>, locations: Vec<Point>) {
-    locations
-        .iter()
-        .for_each(|&p| *grid.get_mut(p).unwrap() = '.');
+pub fn part_one(grid: &Grid<char>) -> Result<usize> {
+    Ok(removal_rounds(grid).count(|&round| round == Some(1)))
 }
 
-fn tp_neighbor_count_less_than_four(grid: &Grid<char>, p: Point) -> bool {
-    grid.neighbors8(p)
-        .into_iter()
-        .filter(|&p| grid.get(p).unwrap() == &'@')
-        .count()
-        < 4
+pub fn part_two(grid: &Grid<char>) -> Result<usize> {
+    Ok(removal_rounds(grid).count(|round| round.is_some()))
 }
 
-fn all_tp_locations(grid: &Grid<char>) -> Vec<Point> {
-    grid.iter()
-        .filter(|(_, &v)| v == '@')
-        .map(|(p, _)| p)
-        .collect()
+fn parse_rolls(input: &str) -> Result<Grid<char>> {
+    Ok(parse::run(DAY, input, char_grid(".@"))?)
+}
+
+/// The round in which each roll of paper is removed, counting from 1, when every round takes
+/// away all rolls with fewer than four neighbouring rolls at once. Rolls that are never
+/// removed, and empty cells, are `None`.
+///
+/// Neighbour counts are kept up to date as rolls go, so each round only looks at the
+/// neighbours of the rolls removed in the round before.
+pub fn removal_rounds(grid: &Grid<char>) -> Grid<Option<usize>> {
+    let mut counts: Grid<u8> = Grid::new(grid.width, grid.height, 0);
+    for (p, _) in grid.iter().filter(|(_, &c)| c == '@') {
+        for n in grid.neighbors8(p) {
+            *counts.get_mut(n).unwrap() += 1;
+        }
+    }
+
+    let mut rounds = Grid::new(grid.width, grid.height, None);
+    let mut layer = grid.find_all(|&c| c == '@');
+    layer.retain(|&p| counts.get(p) < Some(&4));
+    for &p in &layer {
+        rounds.set(p, Some(1));
+    }
+
+    let mut round = 1;
+    while !layer.is_empty() {
+        let mut next = vec![];
+        for p in layer {
+            for n in grid.neighbors8(p) {
+                let count = counts.get_mut(n).unwrap();
+                *count = count.saturating_sub(1);
+                if *count < 4 && grid.get(n) == Some(&'@') && rounds.get(n) == Some(&None) {
+                    rounds.set(n, Some(round + 1));
+                    next.push(n);
+                }
+            }
+        }
+        layer = next;
+        round += 1;
+    }
+    rounds
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::ParseError;
 
-    const INPUT: &str = "..@@.@@@@.
-@@@.@.@.@@
-@@@@@.@.@@
-@.@@@@..@.
-@@.@@@@.@@
-.@@@@@@@.@
-.@.@.@.@@@
-@.@@@.@@@@
-.@@@@@@@@.
-@.@.@@@.@.";
+    const INPUT: &str = include_str!("../../inputs/examples/day04-1.txt");
 
     #[test]
     fn test_part_one_example() {
-        let result = part_one(INPUT);
+        let result = part_one(&parse_rolls(INPUT).unwrap()).unwrap();
         assert_eq!(result, 13);
     }
 
     #[test]
     fn test_part_two_example() {
-        let result = part_two(INPUT);
+        let result = part_two(&parse_rolls(INPUT).unwrap()).unwrap();
         assert_eq!(result, 43);
     }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = parse_rolls("..@\n.@\n").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 2, 1, ".@", "expected rows of equal width")
+        );
+    }
+
+    /// The original approach: rescan the whole grid every round, kept as an oracle.
+    fn rescan_rounds(grid: &Grid<char>) -> Grid<Option<usize>> {
+        let mut grid = grid.clone();
+        let mut rounds = Grid::new(grid.width, grid.height, None);
+        for round in 1.. {
+            let candidates = grid.find_all(|&c| c == '@');
+            let candidates = candidates
+                .into_iter()
+                .filter(|&p| {
+                    grid.neighbors8(p)
+                        .filter(|&n| grid.get(n) == Some(&'@'))
+                        .count()
+                        < 4
+                })
+                .collect::<Vec<_>>();
+            if candidates.is_empty() {
+                break;
+            }
+            for p in candidates {
+                grid.set(p, '.');
+                rounds.set(p, Some(round));
+            }
+        }
+        rounds
+    }
+
+    #[test]
+    fn test_removal_rounds() {
+        let grid = parse_rolls(INPUT).unwrap();
+        let rounds = removal_rounds(&grid);
+        assert_eq!(rounds.cells, rescan_rounds(&grid).cells);
+        assert_eq!(rounds.count(|&r| r == Some(1)), 13);
+        assert_eq!(rounds.cells.iter().flatten().max(), Some(&9));
+
+        let block = Grid::from_str("@@@@\n@@@@\n@@@@\n@@@@");
+        let rounds = removal_rounds(&block);
+        assert_eq!(rounds.cells, rescan_rounds(&block).cells);
+        assert_eq!(rounds.count(|&r| r == Some(1)), 4);
+        assert_eq!(rounds.count(|r| r.is_none()), 12);
+    }
 }
@@ -1,82 +1,109 @@
-pub fn part_one(input: &str) -> usize {
-    let (fresh_id_ranges, ingredients) = input.split_once("\n\n").unwrap();
-    let merged = build_merged_ranges(fresh_id_ranges);
-    ingredients
-        .trim()
-        .lines()
-        .map(|num| num.trim().parse::<i64>().unwrap())
-        .filter(|&num| {
-            merged
-                .iter()
-                .any(|&(start, end)| start <= num && num <= end + 1)
-        })
-        .count()
+use crate::answer::Answer;
+use crate::parse::{self, lines, range, unsigned};
+use crate::range_set::RangeSet;
+use crate::solution::{Metadata, Solution};
+use anyhow::Result;
+use nom::character::complete::line_ending;
+use nom::combinator::map;
+use nom::error::context;
+use nom::sequence::{pair, separated_pair};
+
+const DAY: u8 = 5;
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Parsed = (RangeSet, Vec<i64>);
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: DAY,
+            title: "Cafeteria",
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<(RangeSet, Vec<i64>)> {
+        parse_database(input)
+    }
+
+    fn part_one(&self, (fresh, ingredients): &(RangeSet, Vec<i64>)) -> Result<Answer> {
+        Ok(part_one(fresh, ingredients)?.into())
+    }
+
+    fn part_two(&self, (fresh, _): &(RangeSet, Vec<i64>)) -> Result<Answer> {
+        Ok(part_two(fresh)?.into())
+    }
 }
 
-pub fn part_two(input: &str) -> i64 {
-    let (fresh_id_ranges, _) = input.split_once("\n\n").unwrap();
-    build_merged_ranges(fresh_id_ranges)
+pub fn part_one(fresh: &RangeSet, ingredients: &[i64]) -> Result<usize> {
+    Ok(ingredients
         .iter()
-        .map(|(start, end)| end - start + 1)
-        .sum()
+        .filter(|&&num| fresh.contains(num))
+        .count())
 }
 
-fn build_merged_ranges(input: &str) -> Vec<(i64, i64)> {
-    let fresh_id_ranges = input
-        .trim()
-        .lines()
-        .map(|rng| {
-            let (start, end) = rng
-                .split_once("-")
-                .and_then(|(l, r)| Some((l.parse::<i64>().unwrap(), r.parse::<i64>().unwrap())))
-                .expect("could not parse number");
-            (start, end)
-        })
-        .collect::<Vec<_>>();
-
-    merge_ranges(fresh_id_ranges)
+pub fn part_two(fresh: &RangeSet) -> Result<u128> {
+    Ok(fresh.covered())
 }
 
-/// Takes a vector of (start, end) and merges the overlapping ranges. Returns new vector
-fn merge_ranges(mut fresh_id_ranges: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
-    fresh_id_ranges.sort_by_key(|r| r.0);
-    let mut merged = vec![fresh_id_ranges[0]];
-    for range in fresh_id_ranges.iter().skip(1) {
-        let last = merged.last_mut().unwrap();
-        if range.0 <= last.1 + 1 {
-            last.1 = last.1.max(range.1);
-        } else {
-            merged.push((range.0, range.1));
-        }
-    }
-    merged
+/// The fresh ID ranges and, after a blank line, the available ingredient IDs.
+fn parse_database(input: &str) -> Result<(RangeSet, Vec<i64>)> {
+    Ok(parse::run(
+        DAY,
+        input,
+        separated_pair(
+            map(
+                lines(context("expected a range like `3-5`", range)),
+                RangeSet::from_iter,
+            ),
+            pair(
+                line_ending,
+                context("expected a blank line after the ranges", line_ending),
+            ),
+            lines(unsigned),
+        ),
+    )?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::ParseError;
 
-    const INPUT: &str = "3-5
-10-14
-16-20
-12-18
-
-1
-5
-8
-11
-17
-32";
+    const INPUT: &str = include_str!("../../inputs/examples/day05-1.txt");
 
     #[test]
     fn test_part_one_example() {
-        let result = part_one(INPUT);
+        let (fresh, ingredients) = parse_database(INPUT).unwrap();
+        let result = part_one(&fresh, &ingredients).unwrap();
         assert_eq!(result, 3);
     }
 
     #[test]
     fn test_part_two_example() {
-        let result = part_two(INPUT);
+        let (fresh, _) = parse_database(INPUT).unwrap();
+        let result = part_two(&fresh).unwrap();
         assert_eq!(result, 14);
     }
+
+    #[test]
+    fn test_range_ends_are_inclusive() {
+        let (fresh, ingredients) = parse_database("3-5\n\n2\n3\n5\n6\n").unwrap();
+        assert_eq!(part_one(&fresh, &ingredients).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = parse_database("3-5\n10-14\n").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 3, 1, "", "expected a blank line after the ranges")
+        );
+
+        let err = parse_database("3-5\n10-1x\n\n1\n").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 2, 4, "1x", "expected a number")
+        );
+    }
 }
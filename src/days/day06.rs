@@ -1,119 +1,265 @@
-pub fn part_one(input: &str) -> i64 {
-    let grid = input
-        .lines()
-        .map(|l| l.trim().split_ascii_whitespace().collect::<Vec<_>>())
-        .collect::<Vec<Vec<_>>>();
-
-    let mut sum = 0;
-    for (i, op) in grid.last().unwrap().iter().enumerate() {
-        let col = grid
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| *i != grid.len() - 1)
-            .map(|(_, row)| row.into_iter().nth(i).unwrap())
-            .map(|&x| x.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
-
-        let res = match *op {
-            "*" => col.iter().fold(1, |acc, x| acc * x),
-            "+" => col.iter().fold(0, |acc, x| acc + x),
-            _ => todo!(),
-        };
-        sum += res;
+use crate::answer::Answer;
+use crate::cli::ParseError;
+use crate::solution::{Metadata, Solution};
+use anyhow::Result;
+use std::ops::Range;
+
+const DAY: u8 = 6;
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Parsed = Worksheet;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: DAY,
+            title: "Trash Compactor",
+        }
     }
 
-    sum
+    fn parse(&self, input: &str) -> Result<Worksheet> {
+        parse_worksheet(input)
+    }
+
+    fn part_one(&self, sheet: &Worksheet) -> Result<Answer> {
+        Ok(part_one(sheet)?.into())
+    }
+
+    fn part_two(&self, sheet: &Worksheet) -> Result<Answer> {
+        Ok(part_two(sheet)?.into())
+    }
 }
 
-pub fn part_two(input: &str) -> u64 {
-    let lines: Vec<&str> = input.lines().collect();
-    let data_lines = &lines[..lines.len() - 1];
-    let op_line = lines.last().unwrap();
+/// The rows of digits, padded with spaces to the same width, split into problems at the columns
+/// that are blank in every row.
+#[derive(Debug, Clone)]
+pub struct Worksheet {
+    rows: Vec<Vec<char>>,
+    problems: Vec<Problem>,
+}
 
-    let grid: Vec<Vec<char>> = data_lines
-        .iter()
-        .map(|line| line.chars().collect())
-        .collect();
+/// One problem's operator and the columns its numbers occupy.
+#[derive(Debug, Clone)]
+struct Problem {
+    op: char,
+    columns: Range<usize>,
+}
 
-    let row_count = grid.len();
-    let col_count = grid.iter().map(|r| r.len()).max().unwrap_or(0);
+impl Problem {
+    /// Adds or multiplies `nums`, or `None` if the result does not fit in a `u64`.
+    fn apply(&self, nums: &[u64]) -> Option<u64> {
+        match self.op {
+            '+' => nums.iter().try_fold(0u64, |acc, &n| acc.checked_add(n)),
+            _ => nums.iter().try_fold(1u64, |acc, &n| acc.checked_mul(n)),
+        }
+    }
+}
 
-    let mut problems: Vec<(char, Vec<usize>)> = vec![];
-    let mut current_cols: Vec<usize> = vec![];
-    let mut current_op: Option<char> = None;
+/// Reads each problem's numbers left to right along the rows.
+pub fn part_one(sheet: &Worksheet) -> Result<u64> {
+    grand_total(sheet, |i, problem| {
+        sheet
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(row, cells)| {
+                let text = cells[problem.columns.clone()].iter().collect::<String>();
+                let line = row + 1;
+                match text.trim() {
+                    "" => Err(ParseError::malformed(
+                        DAY,
+                        line,
+                        1,
+                        cells.iter().collect::<String>().trim_end(),
+                        format!("expected a number for problem {}", i + 1),
+                    )),
+                    num => {
+                        let column = problem.columns.start + text.find(num).unwrap_or(0) + 1;
+                        num.parse().map_err(|_| {
+                            ParseError::malformed(DAY, line, column, num, "expected a number")
+                        })
+                    }
+                }
+            })
+            .collect()
+    })
+}
 
-    for col in (0..col_count).rev() {
-        let is_separator =
-            (0..row_count).all(|row| grid[row].get(col).unwrap_or(&' ').is_whitespace());
+/// Reads each problem's numbers top to bottom down the columns.
+pub fn part_two(sheet: &Worksheet) -> Result<u64> {
+    grand_total(sheet, |_, problem| {
+        problem
+            .columns
+            .clone()
+            .filter_map(|col| {
+                let digits = (0..sheet.rows.len())
+                    .map(|row| (row, sheet.rows[row][col]))
+                    .filter(|(_, c)| !c.is_whitespace())
+                    .collect::<Vec<_>>();
 
-        let op_char = op_line.chars().nth(col).unwrap_or(' ');
+                let (first_row, _) = *digits.first()?;
+                let num_str: String = digits.iter().map(|(_, c)| *c).collect();
+                Some(num_str.parse().map_err(|_| {
+                    ParseError::malformed(
+                        DAY,
+                        first_row + 1,
+                        col + 1,
+                        &num_str,
+                        "expected a number",
+                    )
+                }))
+            })
+            .collect()
+    })
+}
 
-        if is_separator {
-            if !current_cols.is_empty() {
-                if let Some(op) = current_op {
-                    problems.push((op, current_cols.clone()));
-                }
-                current_cols.clear();
-                current_op = None;
-            }
-        } else {
-            current_cols.push(col);
-            if op_char == '+' || op_char == '*' {
-                current_op = Some(op_char);
-            }
+/// Sums every problem's result, reading the numbers of problem `i` with `numbers`.
+fn grand_total(
+    sheet: &Worksheet,
+    numbers: impl Fn(usize, &Problem) -> Result<Vec<u64>, ParseError>,
+) -> Result<u64> {
+    sheet
+        .problems
+        .iter()
+        .enumerate()
+        .try_fold(0u64, |total, (i, problem)| {
+            let result = problem
+                .apply(&numbers(i, problem)?)
+                .ok_or_else(|| anyhow::anyhow!("problem {} overflows u64", i + 1))?;
+            total
+                .checked_add(result)
+                .ok_or_else(|| anyhow::anyhow!("the grand total overflows u64"))
+        })
+}
+
+/// Parses rows of digits and spaces followed by an operator line, where each problem has a `+`
+/// or `*` somewhere beneath it.
+fn parse_worksheet(input: &str) -> Result<Worksheet> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let (op_line, data_lines) = lines
+        .split_last()
+        .ok_or_else(|| ParseError::at(DAY, input, input, "expected an operator line"))?;
+
+    for line in data_lines {
+        if let Some(text) = invalid(line, |c| c.is_ascii_digit()) {
+            return Err(ParseError::at(DAY, input, text, "expected a digit").into());
         }
     }
-    if !current_cols.is_empty() {
-        if let Some(op) = current_op {
-            problems.push((op, current_cols));
-        }
+    if let Some(text) = invalid(op_line, |c| matches!(c, '+' | '*')) {
+        return Err(ParseError::at(DAY, input, text, "expected `+` or `*`").into());
     }
 
-    let mut total = 0u64;
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let pad = |line: &str| {
+        let mut cells = line.chars().collect::<Vec<_>>();
+        cells.resize(width, ' ');
+        cells
+    };
+    let rows = data_lines.iter().map(|l| pad(l)).collect::<Vec<_>>();
+    let ops = pad(op_line);
 
-    for (op, cols) in problems {
-        let nums: Vec<u64> = cols
+    let is_separator = |col: usize| rows.iter().all(|row| row[col].is_whitespace());
+    let mut problems = vec![];
+    let mut col = 0;
+    while col < width {
+        if is_separator(col) {
+            col += 1;
+            continue;
+        }
+        let start = col;
+        while col < width && !is_separator(col) {
+            col += 1;
+        }
+        let op = ops[start..col]
             .iter()
-            .filter_map(|&col| {
-                let num_str: String = (0..row_count)
-                    .filter_map(|row| grid[row].get(col))
-                    .filter(|c| !c.is_whitespace())
-                    .collect();
-
-                if num_str.is_empty() {
-                    None
-                } else {
-                    num_str.parse().ok()
-                }
-            })
-            .collect();
-
-        let res: u64 = match op {
-            '+' => nums.iter().sum(),
-            '*' => nums.iter().product(),
-            _ => unreachable!(),
-        };
-        total += res;
+            .copied()
+            .find(|c| !c.is_whitespace())
+            .ok_or_else(|| {
+                ParseError::malformed(
+                    DAY,
+                    lines.len(),
+                    start + 1,
+                    "",
+                    "expected `+` or `*` under the problem",
+                )
+            })?;
+        problems.push(Problem {
+            op,
+            columns: start..col,
+        });
     }
 
-    total
+    Ok(Worksheet { rows, problems })
+}
+
+/// The first character of `line` that is neither whitespace nor `valid`.
+fn invalid(line: &str, valid: fn(char) -> bool) -> Option<&str> {
+    line.char_indices()
+        .find(|&(_, c)| !valid(c) && !c.is_whitespace())
+        .map(|(i, c)| &line[i..i + c.len_utf8()])
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "123 328  51 64 \n 45 64  387 23 \n  6 98  215 314\n *   +   *   + ";
+    const INPUT: &str = include_str!("../../inputs/examples/day06-1.txt");
 
     #[test]
     fn test_part_one_example() {
-        let result = part_one(INPUT);
+        let result = part_one(&parse_worksheet(INPUT).unwrap()).unwrap();
         assert_eq!(result, 4277556);
     }
 
     #[test]
     fn test_part_two_example() {
-        let result = part_two(INPUT);
+        let result = part_two(&parse_worksheet(INPUT).unwrap()).unwrap();
         assert_eq!(result, 3263827);
     }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = parse_worksheet("1 2\n3 4\n+ -").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 3, 3, "-", "expected `+` or `*`")
+        );
+
+        let sheet = parse_worksheet("1 2\n3\n+ *").unwrap();
+        let err = part_one(&sheet).unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 2, 1, "3", "expected a number for problem 2")
+        );
+
+        let err = parse_worksheet("1 2\n3 x\n+ *").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 2, 3, "x", "expected a digit")
+        );
+
+        let err = parse_worksheet("1 2\n3 4\n+  ").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 3, 3, "", "expected `+` or `*` under the problem")
+        );
+
+        assert!(parse_worksheet("").is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let sheet = parse_worksheet("4294967296\n4294967296\n*").unwrap();
+        let err = part_one(&sheet).unwrap_err();
+        assert_eq!(err.to_string(), "problem 1 overflows u64");
+
+        let big = u64::MAX.to_string();
+        let sheet = parse_worksheet(&format!("{} 1\n+{:>21}", big, "+")).unwrap();
+        let err = part_one(&sheet).unwrap_err();
+        assert_eq!(err.to_string(), "the grand total overflows u64");
+
+        let sheet = parse_worksheet("99\n99\n*").unwrap();
+        assert_eq!(part_two(&sheet).unwrap(), 99 * 99);
+    }
 }
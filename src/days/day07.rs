@@ -1,114 +1,258 @@
+use crate::answer::Answer;
+use crate::beam::{Beams, Behaviour};
+use crate::cli::ParseError;
+use crate::parse::{self, char_grid};
+use crate::solution::{Metadata, Solution};
 use crate::{Direction, Grid, Point};
-use itertools::Itertools;
+use anyhow::Result;
 
-pub fn part_one(input: &str) -> isize {
-    let grid = Grid::from_str(input.trim());
-    let start = grid.find_value(&'S').expect("no starting position found");
+const DAY: u8 = 7;
 
-    let mut split_count = 0;
-    let mut beams = vec![start];
-    for _ in start.y..grid.height as i64 {
-        let mut new_beams = Vec::new();
+pub struct Day07;
 
-        for b in beams {
-            if !grid.in_bounds(b) {
-                continue;
-            }
+impl Solution for Day07 {
+    type Parsed = (Grid<char>, Point);
 
-            let moved = b.step(Direction::Down);
-            if let Some(c) = grid.get(moved) {
-                match c {
-                    '.' => {
-                        new_beams.push(moved);
-                    }
-                    '^' => {
-                        split_count += 1;
-                        new_beams.push(Point::new(moved.x - 1, moved.y));
-                        new_beams.push(Point::new(moved.x + 1, moved.y));
-                    }
-                    _ => unreachable!(),
-                }
-            }
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: DAY,
+            title: "Laboratories",
         }
-        beams = new_beams
-            .iter()
-            .unique()
-            .map(|&p| p)
-            .collect::<Vec<_>>()
-            .clone();
     }
-    split_count
+
+    fn parse(&self, input: &str) -> Result<(Grid<char>, Point)> {
+        parse_manifold(input)
+    }
+
+    fn part_one(&self, (grid, start): &(Grid<char>, Point)) -> Result<Answer> {
+        Ok(part_one(grid, *start)?.into())
+    }
+
+    fn part_two(&self, (grid, start): &(Grid<char>, Point)) -> Result<Answer> {
+        Ok(part_two(grid, *start)?.into())
+    }
 }
 
-pub fn part_two(input: &str) -> usize {
-    let grid = Grid::from_str(input.trim());
-    let start = grid.find_value(&'S').expect("no starting position found");
+pub fn part_one(grid: &Grid<char>, start: Point) -> Result<usize> {
+    let beams = Beams::new()
+        .with('S', Behaviour::Pass)
+        .with('^', Behaviour::Fork);
+    Ok(beams.trace(grid, start, Direction::Down)?.splits)
+}
 
-    let mut beams = vec![start];
+/// Counts the timelines a single tachyon ends up in, tracking how many timelines have a beam in
+/// each column instead of the beams themselves.
+pub fn part_two(grid: &Grid<char>, start: Point) -> Result<u128> {
+    let add = |a: u128, b: u128| {
+        a.checked_add(b)
+            .ok_or_else(|| anyhow::anyhow!("more than {} timelines", u128::MAX))
+    };
 
-    let mut split_count = 1;
+    // Column `x` lives at index `x + 1`, so a split at either edge has somewhere to go.
+    let mut timelines = vec![0u128; grid.width + 2];
+    timelines[start.x as usize + 1] = 1;
+    // Timelines whose beam was split off either side of the manifold.
+    let mut exited = 0u128;
 
-    for _ in start.y..grid.height as i64 {
-        let mut new_beams = Vec::new();
-        for b in beams {
-            if !grid.in_bounds(b) {
+    for y in start.y + 1..grid.height as i64 {
+        let mut next = vec![0u128; grid.width + 2];
+        for x in 0..grid.width as i64 {
+            let count = timelines[x as usize + 1];
+            if count == 0 {
                 continue;
             }
+            let targets = match grid.get(Point::new(x, y)) {
+                Some('.') => vec![x],
+                Some('^') => vec![x - 1, x + 1],
+                _ => vec![],
+            };
+            for target in targets {
+                let slot = &mut next[(target + 1) as usize];
+                *slot = add(*slot, count)?;
+            }
+        }
+        exited = add(exited, next[0])?;
+        exited = add(exited, next[grid.width + 1])?;
+        next[0] = 0;
+        next[grid.width + 1] = 0;
+        timelines = next;
+    }
 
-            let moved = b.step(Direction::Down);
-            if let Some(c) = grid.get(moved) {
-                match c {
-                    '.' => {
-                        new_beams.push(moved);
-                    }
-                    '^' => {
+    timelines.into_iter().try_fold(exited, add)
+}
+
+/// The manifold and the position of its single `S`.
+fn parse_manifold(input: &str) -> Result<(Grid<char>, Point)> {
+    let input = input.trim();
+    let grid = parse::run(DAY, input, char_grid(".^S"))?;
+    let start = grid
+        .find_value(&'S')
+        .ok_or_else(|| ParseError::at(DAY, input, input, "expected a starting position `S`"))?;
+    if let Some((p, _)) = grid.iter().find(|&(p, &c)| c == 'S' && p != start) {
+        let (line, column) = (p.y as usize + 1, p.x as usize + 1);
+        return Err(ParseError::malformed(DAY, line, column, "S", "expected `.` or `^`").into());
+    }
+    Ok((grid, start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    const INPUT: &str = include_str!("../../inputs/examples/day07-1.txt");
+
+    /// One entry per timeline, each followed until its beam leaves the manifold through the
+    /// bottom or a side, as an oracle for small grids.
+    fn enumerate_timelines(grid: &Grid<char>, start: Point) -> usize {
+        let mut beams = vec![start];
+        let mut finished = 0;
+
+        while !beams.is_empty() {
+            let mut new_beams = Vec::new();
+            for b in beams {
+                let moved = b.step(Direction::Down);
+                match grid.get(moved) {
+                    Some('.') => new_beams.push(moved),
+                    Some('^') => {
                         new_beams.push(Point::new(moved.x - 1, moved.y));
                         new_beams.push(Point::new(moved.x + 1, moved.y));
                     }
-                    _ => unreachable!(),
+                    Some(_) => {}
+                    None => finished += 1,
                 }
             }
+            beams = new_beams;
         }
 
-        beams = new_beams;
-        split_count = split_count.max(beams.len());
+        finished
     }
 
-    split_count
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// The original part one, stepping every distinct beam down a row at a time.
+    fn count_splits(grid: &Grid<char>, start: Point) -> usize {
+        let mut split_count = 0;
+        let mut beams = vec![start];
+        for _ in start.y..grid.height as i64 {
+            let mut new_beams = Vec::new();
+            for b in beams {
+                if !grid.in_bounds(b) {
+                    continue;
+                }
 
-    const INPUT: &str = "
-.......S.......
-...............
-.......^.......
-...............
-......^.^......
-...............
-.....^.^.^.....
-...............
-....^.^...^....
-...............
-...^.^...^.^...
-...............
-..^...^.....^..
-...............
-.^.^.^.^.^...^.
-...............
-";
+                let moved = b.step(Direction::Down);
+                match grid.get(moved) {
+                    Some('.') => new_beams.push(moved),
+                    Some('^') => {
+                        split_count += 1;
+                        new_beams.push(Point::new(moved.x - 1, moved.y));
+                        new_beams.push(Point::new(moved.x + 1, moved.y));
+                    }
+                    _ => {}
+                }
+            }
+            beams = new_beams.into_iter().unique().collect();
+        }
+        split_count
+    }
 
     #[test]
     fn test_part_one_example() {
-        let result = part_one(INPUT);
+        let (grid, start) = parse_manifold(INPUT).unwrap();
+        let result = part_one(&grid, start).unwrap();
         assert_eq!(result, 21);
     }
 
     #[test]
     fn test_part_two_example() {
-        let result = part_two(INPUT);
+        let (grid, start) = parse_manifold(INPUT).unwrap();
+        let result = part_two(&grid, start).unwrap();
         assert_eq!(result, 40);
     }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = parse_manifold("...\n.^.\n").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 1, 1, "...\n.^.", "expected a starting position `S`")
+        );
+
+        let err = parse_manifold(".S.\n.S.\n").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 2, 2, "S", "expected `.` or `^`")
+        );
+    }
+
+    #[test]
+    fn test_matches_enumeration() {
+        let grids = [
+            INPUT,
+            "..S..\n.....\n..^..\n.^.^.\n^...^",
+            "S\n^\n.",
+            "..S.\n..^.\n.^^.\n....",
+            "S..\n^..\n...\n.^.\n...",
+        ];
+        for input in grids {
+            let (grid, start) = parse_manifold(input).unwrap();
+            assert_eq!(
+                part_two(&grid, start).unwrap(),
+                enumerate_timelines(&grid, start) as u128,
+                "{}",
+                input
+            );
+        }
+
+        // One timeline leaves on the left at the first splitter, the other splits in two below.
+        let (grid, start) = parse_manifold("S..\n^..\n...\n.^.\n...").unwrap();
+        assert_eq!(part_two(&grid, start).unwrap(), 3);
+        assert_eq!(enumerate_timelines(&grid, start), 3);
+    }
+
+    #[test]
+    fn test_splits_match_original() {
+        let grids = [
+            INPUT,
+            "..S..\n.....\n..^..\n.^.^.\n^...^",
+            "S\n^\n.",
+            "..S.\n..^.\n.^^.\n....",
+        ];
+        for input in grids {
+            let (grid, start) = parse_manifold(input).unwrap();
+            assert_eq!(
+                part_one(&grid, start).unwrap(),
+                count_splits(&grid, start),
+                "{}",
+                input
+            );
+        }
+
+        let (grid, start) = parse_manifold("..S.\n..^.\n.^^.\n....").unwrap();
+        assert_eq!(part_one(&grid, start).unwrap(), 2);
+        assert_eq!(part_two(&grid, start).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_many_timelines() {
+        // A full pyramid of splitters doubles the timelines on every one of its 100 rows.
+        let (width, centre) = (203, 101i64);
+        let mut rows = vec![];
+        for k in 0..100i64 {
+            let row = (0..width as i64)
+                .map(|x| {
+                    let offset = x - centre;
+                    match offset.abs() <= k && (offset + k) % 2 == 0 {
+                        true => '^',
+                        false => '.',
+                    }
+                })
+                .collect::<String>();
+            rows.push(row);
+            rows.push(".".repeat(width));
+        }
+        let start = format!("{}S{}", ".".repeat(101), ".".repeat(101));
+        let input = format!("{}\n{}", start, rows.join("\n"));
+        let (grid, start) = parse_manifold(&input).unwrap();
+        assert_eq!(part_two(&grid, start).unwrap(), 1u128 << 100);
+    }
 }
@@ -1,28 +1,132 @@
-pub fn part_one(_input: &str) -> isize {
-    0
+use crate::answer::Answer;
+use crate::dsu::Dsu;
+use crate::parse::{self, lines, signed};
+use crate::solution::{Metadata, Solution};
+use crate::Point3;
+use anyhow::Result;
+use nom::character::complete::char;
+use nom::error::context;
+use nom::sequence::{preceded, tuple};
+
+const DAY: u8 = 8;
+
+/// How many of the closest pairs part one connects.
+const CONNECTIONS: usize = 1000;
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Parsed = Vec<Point3>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: DAY,
+            title: "Playground",
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<Point3>> {
+        parse_boxes(input)
+    }
+
+    fn part_one(&self, boxes: &Vec<Point3>) -> Result<Answer> {
+        Ok(part_one(boxes)?.into())
+    }
+
+    fn part_two(&self, boxes: &Vec<Point3>) -> Result<Answer> {
+        Ok(part_two(boxes)?.into())
+    }
+}
+
+pub fn part_one(boxes: &[Point3]) -> Result<usize> {
+    circuit_product(boxes, CONNECTIONS)
+}
+
+/// Connects the `connections` closest pairs of junction boxes and multiplies the sizes of the
+/// three largest circuits. Pairs already in the same circuit still use up a connection.
+pub fn circuit_product(boxes: &[Point3], connections: usize) -> Result<usize> {
+    let mut circuits = Dsu::new(boxes.len());
+    for (_, a, b) in closest_pairs(boxes).into_iter().take(connections) {
+        circuits.union(a, b);
+    }
+
+    let mut sizes = circuits
+        .components()
+        .iter()
+        .map(Vec::len)
+        .collect::<Vec<_>>();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(sizes.iter().take(3).product())
+}
+
+/// Keeps connecting the closest pairs until every box is in one circuit, then multiplies the
+/// X coordinates of the last two boxes joined.
+pub fn part_two(boxes: &[Point3]) -> Result<i64> {
+    let mut circuits = Dsu::new(boxes.len());
+    for (_, a, b) in closest_pairs(boxes) {
+        if circuits.union(a, b) && circuits.count() == 1 {
+            return Ok(boxes[a].x * boxes[b].x);
+        }
+    }
+    anyhow::bail!("expected at least two junction boxes")
 }
 
-pub fn part_two(_input: &str) -> usize {
-    0
+fn parse_boxes(input: &str) -> Result<Vec<Point3>> {
+    let position = tuple((
+        signed,
+        preceded(char(','), signed),
+        preceded(char(','), signed),
+    ));
+    let boxes = parse::run(
+        DAY,
+        input,
+        lines(context("expected a position like `162,817,812`", position)),
+    )?;
+    Ok(boxes
+        .into_iter()
+        .map(|(x, y, z)| Point3::new(x, y, z))
+        .collect())
+}
+
+/// Every pair of boxes with its squared distance, closest first.
+fn closest_pairs(boxes: &[Point3]) -> Vec<(i64, usize, usize)> {
+    let mut pairs = vec![];
+    for (a, p) in boxes.iter().enumerate() {
+        for (b, q) in boxes.iter().enumerate().skip(a + 1) {
+            pairs.push((p.distance_squared(*q), a, b));
+        }
+    }
+    pairs.sort_unstable();
+    pairs
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::ParseError;
 
-    const INPUT: &str = "";
+    const INPUT: &str = include_str!("../../inputs/examples/day08-1.txt");
 
     #[test]
-    #[ignore]
     fn test_part_one_example() {
-        let result = part_one(INPUT);
-        assert_eq!(result, 21);
+        let result = circuit_product(&parse_boxes(INPUT).unwrap(), 10).unwrap();
+        assert_eq!(result, 40);
     }
 
     #[test]
-    #[ignore]
     fn test_part_two_example() {
-        let result = part_two(INPUT);
-        assert_eq!(result, 40);
+        let result = part_two(&parse_boxes(INPUT).unwrap()).unwrap();
+        assert_eq!(result, 25272);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = parse_boxes("1,2,3\n4,5\n").unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::malformed(DAY, 2, 1, "4", "expected a position like `162,817,812`")
+        );
+
+        assert!(part_two(&parse_boxes("1,2,3\n").unwrap()).is_err());
     }
 }
@@ -1,3 +1,5 @@
+use crate::solution::DynSolution;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -6,3 +8,40 @@ pub mod day05;
 pub mod day06;
 pub mod day07;
 pub mod day08;
+
+/// Every implemented day, in order.
+pub static SOLUTIONS: &[&dyn DynSolution] = &[
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+];
+
+pub fn get(day: u8) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS.iter().copied().find(|s| s.metadata().day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_sorted_and_unique() {
+        let days = SOLUTIONS
+            .iter()
+            .map(|s| s.metadata().day)
+            .collect::<Vec<_>>();
+        assert!(days.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn get_finds_registered_days() {
+        assert_eq!(get(1).map(|s| s.metadata().day), Some(1));
+        assert!(get(0).is_none());
+        assert!(get(26).is_none());
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Disjoint sets over the indices `0..len`, with path compression and union by size.
+#[derive(Debug, Clone, Default)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl Dsu {
+    /// `n` elements, each in a set of its own.
+    pub fn new(n: usize) -> Self {
+        Dsu {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    /// Adds a new element in a set of its own and returns its index.
+    pub fn add(&mut self) -> usize {
+        let i = self.parent.len();
+        self.parent.push(i);
+        self.size.push(1);
+        self.count += 1;
+        i
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// The representative of the set holding `i`.
+    pub fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut i = i;
+        while self.parent[i] != root {
+            i = std::mem::replace(&mut self.parent[i], root);
+        }
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`, returning false if they already were one.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = match self.size[a] >= self.size[b] {
+            true => (a, b),
+            false => (b, a),
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set holding `i`.
+    pub fn size(&mut self, i: usize) -> usize {
+        let root = self.find(i);
+        self.size[root]
+    }
+
+    /// How many disjoint sets there are.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Every set, each in ascending order, ordered by their smallest element.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut slot = HashMap::new();
+        let mut components: Vec<Vec<usize>> = vec![];
+        for i in 0..self.len() {
+            let root = self.find(i);
+            let at = *slot.entry(root).or_insert_with(|| {
+                components.push(vec![]);
+                components.len() - 1
+            });
+            components[at].push(i);
+        }
+        components
+    }
+}
+
+/// A [`Dsu`] over arbitrary keys, such as [`Point`](crate::Point)s. Keys are added the first
+/// time they are seen.
+#[derive(Debug, Clone)]
+pub struct KeyedDsu<K> {
+    index: HashMap<K, usize>,
+    keys: Vec<K>,
+    sets: Dsu,
+}
+
+impl<K> Default for KeyedDsu<K> {
+    fn default() -> Self {
+        KeyedDsu {
+            index: HashMap::new(),
+            keys: vec![],
+            sets: Dsu::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> KeyedDsu<K> {
+    pub fn new() -> Self {
+        KeyedDsu::default()
+    }
+
+    /// Adds `key` in a set of its own, unless it is already known.
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(&i) = self.index.get(&key) {
+            return i;
+        }
+        let i = self.sets.add();
+        self.index.insert(key.clone(), i);
+        self.keys.push(key);
+        i
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// The representative key of the set holding `key`.
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let i = *self.index.get(key)?;
+        let root = self.sets.find(i);
+        Some(&self.keys[root])
+    }
+
+    /// Merges the sets holding `a` and `b`, adding either if needed. Returns false if they
+    /// already were one.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        self.sets.union(a, b)
+    }
+
+    /// Whether both keys are known and in the same set.
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        match (self.index.get(a), self.index.get(b)) {
+            (Some(&a), Some(&b)) => self.sets.same(a, b),
+            _ => false,
+        }
+    }
+
+    /// Size of the set holding `key`, or `None` for an unknown key.
+    pub fn size(&mut self, key: &K) -> Option<usize> {
+        let i = *self.index.get(key)?;
+        Some(self.sets.size(i))
+    }
+
+    pub fn count(&self) -> usize {
+        self.sets.count()
+    }
+
+    /// Every set, with keys in the order they were first added.
+    pub fn components(&mut self) -> Vec<Vec<K>> {
+        self.sets
+            .components()
+            .into_iter()
+            .map(|set| set.into_iter().map(|i| self.keys[i].clone()).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    fn union_and_find() {
+        let mut dsu = Dsu::new(6);
+        assert_eq!(dsu.count(), 6);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2));
+
+        assert!(dsu.same(0, 3));
+        assert!(!dsu.same(0, 4));
+        assert_eq!(dsu.size(2), 4);
+        assert_eq!(dsu.size(5), 1);
+        assert_eq!(dsu.count(), 3);
+        assert_eq!(dsu.components(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+
+        let i = dsu.add();
+        assert_eq!((i, dsu.len(), dsu.count()), (6, 7, 4));
+    }
+
+    #[test]
+    fn long_chains_compress() {
+        let mut dsu = Dsu::new(100_000);
+        for i in 1..dsu.len() {
+            dsu.union(i - 1, i);
+        }
+        assert_eq!(dsu.count(), 1);
+        assert_eq!(dsu.size(0), 100_000);
+    }
+
+    #[test]
+    fn keyed_by_points() {
+        let mut dsu = KeyedDsu::new();
+        assert!(dsu.union(Point::new(0, 0), Point::new(0, 1)));
+        assert!(dsu.union(Point::new(5, 5), Point::new(5, 6)));
+        assert!(!dsu.union(Point::new(0, 1), Point::new(0, 0)));
+        dsu.insert(Point::new(9, 9));
+
+        assert_eq!(dsu.len(), 5);
+        assert_eq!(dsu.count(), 3);
+        assert!(dsu.same(&Point::new(0, 0), &Point::new(0, 1)));
+        assert!(!dsu.same(&Point::new(0, 0), &Point::new(7, 7)));
+        assert_eq!(dsu.size(&Point::new(5, 6)), Some(2));
+        assert_eq!(dsu.size(&Point::new(7, 7)), None);
+        assert_eq!(dsu.find(&Point::new(0, 1)), Some(&Point::new(0, 0)));
+        assert_eq!(
+            dsu.components(),
+            vec![
+                vec![Point::new(0, 0), Point::new(0, 1)],
+                vec![Point::new(5, 5), Point::new(5, 6)],
+                vec![Point::new(9, 9)],
+            ]
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::answers::AnswerStore;
+
+/// Worked examples from the puzzle text, `inputs/examples/dayNN-K.txt` with the expected
+/// answers alongside in `dayNN-K.json`.
+#[derive(Debug, Clone)]
+pub struct Examples {
+    pub dir: PathBuf,
+}
+
+impl Default for Examples {
+    fn default() -> Self {
+        Examples {
+            dir: PathBuf::from("inputs/examples"),
+        }
+    }
+}
+
+impl Examples {
+    pub fn path(&self, day: u8, example: u32) -> PathBuf {
+        self.dir.join(format!("day{:02}-{}.txt", day, example))
+    }
+
+    pub fn input(&self, day: u8, example: u32) -> Result<String> {
+        let path = self.path(day, example);
+        fs::read_to_string(&path)
+            .with_context(|| format!("could not read example {}", path.display()))
+    }
+
+    pub fn answers(&self, example: u32) -> AnswerStore {
+        AnswerStore::example(&self.dir, example)
+    }
+
+    /// The example numbers that exist for `day`, in order.
+    pub fn list(&self, day: u8) -> Result<Vec<u32>> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Ok(vec![]);
+        };
+
+        let prefix = format!("day{:02}-", day);
+        let mut examples = vec![];
+        for entry in entries {
+            let name = entry?.file_name();
+            let number = name
+                .to_str()
+                .and_then(|n| n.strip_prefix(&prefix))
+                .and_then(|n| n.strip_suffix(".txt"))
+                .and_then(|n| n.parse::<u32>().ok());
+            examples.extend(number);
+        }
+        examples.sort_unstable();
+        Ok(examples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::Verdict;
+    use crate::days;
+    use crate::solution::Part;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn list_examples() {
+        let dir = temp_dir("examples");
+        let examples = Examples { dir: dir.clone() };
+        assert!(examples.list(1).unwrap().is_empty());
+
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["day01-2.txt", "day01-1.txt", "day01-1.json", "day10-1.txt"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        assert_eq!(examples.list(1).unwrap(), vec![1, 2]);
+        assert_eq!(examples.list(10).unwrap(), vec![1]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn registered_examples_pass() {
+        let examples = Examples::default();
+        for solution in days::SOLUTIONS {
+            let day = solution.metadata().day;
+            for example in examples.list(day).unwrap() {
+                let input = examples.input(day, example).unwrap();
+                let report = solution.solve(&input, &Part::ALL).unwrap();
+                let answers = examples.answers(example);
+
+                for result in report.parts {
+                    let expected = answers.get(day, result.part).unwrap();
+                    let verdict = Verdict::new(expected.clone(), &result.answer.to_string());
+                    assert!(
+                        !matches!(verdict, Verdict::Fail { .. }),
+                        "day {:02} example {} part {}: expected {:?}, got {}",
+                        day,
+                        example,
+                        result.part,
+                        expected,
+                        result.answer
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use anyhow::{bail, Result};
+use std::collections::{HashSet, VecDeque};
 use std::fmt;
 use std::ops::{Add, AddAssign, Mul, Sub};
 
@@ -295,6 +297,7 @@ impl<T> Grid<T> {
 }
 
 impl Grid<char> {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: &str) -> Self {
         let lines: Vec<&str> = input.lines().collect();
         let height = lines.len();
@@ -355,6 +358,269 @@ impl<T> From<Vec<Vec<T>>> for Grid<T> {
         }
     }
 }
+// ============================================================================
+// Point3
+// ============================================================================
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+
+    /// layer
+    pub z: i64,
+}
+
+impl Point3 {
+    pub const ZERO: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(&self) -> i64 {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+
+    pub fn manhattan_to(&self, other: Point3) -> i64 {
+        (*self - other).manhattan()
+    }
+
+    /// Squared straight-line distance, exact where the distance itself would need a float.
+    pub fn distance_squared(&self, other: Point3) -> i64 {
+        let d = *self - other;
+        d.x * d.x + d.y * d.y + d.z * d.z
+    }
+
+    /// The points sharing a face.
+    pub fn neighbors6(&self) -> [Point3; 6] {
+        [
+            Point3::new(self.x - 1, self.y, self.z),
+            Point3::new(self.x + 1, self.y, self.z),
+            Point3::new(self.x, self.y - 1, self.z),
+            Point3::new(self.x, self.y + 1, self.z),
+            Point3::new(self.x, self.y, self.z - 1),
+            Point3::new(self.x, self.y, self.z + 1),
+        ]
+    }
+
+    /// The points sharing a face, edge or corner.
+    pub fn neighbors26(&self) -> [Point3; 26] {
+        let mut neighbors = [*self; 26];
+        let offsets = (-1..=1)
+            .flat_map(|z| (-1..=1).flat_map(move |y| (-1..=1).map(move |x| Point3::new(x, y, z))))
+            .filter(|&d| d != Point3::ZERO);
+        for (n, d) in neighbors.iter_mut().zip(offsets) {
+            *n += d;
+        }
+        neighbors
+    }
+
+    pub fn in_bounds(&self, width: i64, height: i64, depth: i64) -> bool {
+        self.x >= 0
+            && self.x < width
+            && self.y >= 0
+            && self.y < height
+            && self.z >= 0
+            && self.z < depth
+    }
+}
+
+impl Add for Point3 {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl AddAssign for Point3 {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+        self.z += other.z;
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<i64> for Point3 {
+    type Output = Self;
+    fn mul(self, scalar: i64) -> Self {
+        Point3::new(self.x * scalar, self.y * scalar, self.z * scalar)
+    }
+}
+
+impl fmt::Display for Point3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+// ============================================================================
+// Grid3
+// ============================================================================
+
+/// A dense 3D grid, stored layer by layer with each layer row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid3<T> {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    pub cells: Vec<T>,
+}
+
+impl<T: Clone> Grid3<T> {
+    pub fn new(width: usize, height: usize, depth: usize, default: T) -> Self {
+        Grid3 {
+            width,
+            height,
+            depth,
+            cells: vec![default; width * height * depth],
+        }
+    }
+}
+
+impl<T> Grid3<T> {
+    fn index(&self, p: Point3) -> usize {
+        (p.z as usize * self.height + p.y as usize) * self.width + p.x as usize
+    }
+
+    pub fn in_bounds(&self, p: Point3) -> bool {
+        p.in_bounds(self.width as i64, self.height as i64, self.depth as i64)
+    }
+
+    pub fn get(&self, p: Point3) -> Option<&T> {
+        if self.in_bounds(p) {
+            Some(&self.cells[self.index(p)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point3) -> Option<&mut T> {
+        if self.in_bounds(p) {
+            let i = self.index(p);
+            Some(&mut self.cells[i])
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, p: Point3, value: T) {
+        if self.in_bounds(p) {
+            let i = self.index(p);
+            self.cells[i] = value;
+        }
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point3> {
+        let (width, height, depth) = (self.width as i64, self.height as i64, self.depth as i64);
+        (0..depth).flat_map(move |z| {
+            (0..height).flat_map(move |y| (0..width).map(move |x| Point3::new(x, y, z)))
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point3, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn count<F>(&self, predicate: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.cells.iter().filter(|v| predicate(v)).count()
+    }
+
+    /// Very similar to Point3.neighbors6, but this returns only valid Points in bounds.
+    pub fn neighbors6(&self, p: Point3) -> impl Iterator<Item = Point3> + '_ {
+        p.neighbors6().into_iter().filter(|n| self.in_bounds(*n))
+    }
+
+    /// Very similar to Point3.neighbors26, but this returns only valid Points in bounds.
+    pub fn neighbors26(&self, p: Point3) -> impl Iterator<Item = Point3> + '_ {
+        p.neighbors26().into_iter().filter(|n| self.in_bounds(*n))
+    }
+
+    /// Every point reachable from `start` through face neighbours whose cells pass `passable`,
+    /// including `start` itself if it passes.
+    pub fn flood_fill<F>(&self, start: Point3, passable: F) -> HashSet<Point3>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut filled = HashSet::new();
+        let mut queue = VecDeque::new();
+        if self.get(start).is_some_and(&passable) {
+            filled.insert(start);
+            queue.push_back(start);
+        }
+        while let Some(p) = queue.pop_front() {
+            for n in self.neighbors6(p) {
+                if passable(&self.cells[self.index(n)]) && filled.insert(n) {
+                    queue.push_back(n);
+                }
+            }
+        }
+        filled
+    }
+}
+
+impl Grid3<char> {
+    /// Stacks 2D layers, such as the blocks of an input split on blank lines, from `z = 0` up.
+    /// The first layer's size sets the grid's width and height, and every other layer and row
+    /// must match it.
+    pub fn from_layers<'a>(layers: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        let layers = layers
+            .into_iter()
+            .map(|layer| layer.lines().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let height = layers.first().map(|rows| rows.len()).unwrap_or(0);
+        let width = layers
+            .first()
+            .and_then(|rows| rows.first())
+            .map(|row| row.chars().count())
+            .unwrap_or(0);
+
+        for (z, rows) in layers.iter().enumerate() {
+            if rows.len() != height {
+                bail!(
+                    "layer {} has {} rows, expected {}",
+                    z + 1,
+                    rows.len(),
+                    height
+                );
+            }
+            for (y, row) in rows.iter().enumerate() {
+                let len = row.chars().count();
+                if len != width {
+                    bail!(
+                        "layer {}, row {} has width {}, expected {}",
+                        z + 1,
+                        y + 1,
+                        len,
+                        width
+                    );
+                }
+            }
+        }
+
+        Ok(Grid3 {
+            width,
+            height,
+            depth: layers.len(),
+            cells: layers
+                .iter()
+                .flatten()
+                .flat_map(|row| row.chars())
+                .collect(),
+        })
+    }
+}
+
 // ============================================================================
 // Usage examples
 // ============================================================================
@@ -432,4 +698,48 @@ mod tests {
 
         assert_eq!(max_dist, 4);
     }
+
+    #[test]
+    fn point3_arithmetic() {
+        let a = Point3::new(1, 2, 3);
+        let b = Point3::new(4, -2, 0);
+        assert_eq!(a + b, Point3::new(5, 0, 3));
+        assert_eq!(a - b, Point3::new(-3, 4, 3));
+        assert_eq!(a * 2, Point3::new(2, 4, 6));
+        assert_eq!(a.manhattan_to(b), 10);
+        assert_eq!(a.distance_squared(b), 9 + 16 + 9);
+
+        let neighbors = a.neighbors26();
+        assert_eq!(neighbors.iter().collect::<HashSet<_>>().len(), 26);
+        assert!(neighbors.iter().all(|n| n.manhattan_to(a) <= 3 && *n != a));
+        assert!(a.neighbors6().iter().all(|n| n.manhattan_to(a) == 1));
+    }
+
+    #[test]
+    fn grid3_layers_and_flood_fill() {
+        let input = "###\n#.#\n###\n\n#.#\n...\n#.#\n\n###\n#.#\n###";
+        let grid = Grid3::from_layers(input.split("\n\n")).unwrap();
+        assert_eq!((grid.width, grid.height, grid.depth), (3, 3, 3));
+        assert_eq!(grid.get(Point3::new(1, 1, 0)), Some(&'.'));
+        assert_eq!(grid.get(Point3::new(0, 1, 1)), Some(&'.'));
+        assert_eq!(grid.get(Point3::new(0, 0, 3)), None);
+        assert_eq!(grid.neighbors6(Point3::ZERO).count(), 3);
+        assert_eq!(grid.neighbors26(Point3::new(1, 1, 1)).count(), 26);
+
+        let open = grid.flood_fill(Point3::new(1, 1, 1), |&c| c == '.');
+        assert_eq!(open.len(), grid.count(|&c| c == '.'));
+        assert!(grid.flood_fill(Point3::ZERO, |&c| c == '.').is_empty());
+    }
+
+    #[test]
+    fn grid3_rejects_ragged_layers() {
+        let err = Grid3::from_layers(["##\n##", "#\n##"]).unwrap_err();
+        assert_eq!(err.to_string(), "layer 2, row 1 has width 1, expected 2");
+
+        let err = Grid3::from_layers(["##\n##", "##"]).unwrap_err();
+        assert_eq!(err.to_string(), "layer 2 has 1 rows, expected 2");
+
+        let grid = Grid3::from_layers(["##\n#."]).unwrap();
+        assert_eq!(grid.cells.len(), grid.width * grid.height * grid.depth);
+    }
 }
@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::cli::ParseError;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2025";
+
+pub(crate) const USER_AGENT: &str = "github.com/WTFox/aoc2025";
+
+/// The site to talk to, `AOC_BASE_URL` or [`DEFAULT_BASE_URL`].
+pub fn base_url_from_env() -> String {
+    env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string())
+}
+
+/// The session cookie from `AOC_SESSION`, falling back to the contents of `.aoc-session`.
+pub fn session_from_env() -> Option<String> {
+    env::var("AOC_SESSION")
+        .ok()
+        .or_else(|| fs::read_to_string(".aoc-session").ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Reads puzzle inputs from `inputs/dayNN.txt`, downloading and caching any that are missing.
+///
+/// A non-empty cached file is never fetched again. Empty files, such as the placeholders
+/// created by `new`, count as missing. Requests are spaced at least `min_interval` apart,
+/// across runs too, using the time recorded in `inputs/.last-fetch`.
+#[derive(Debug, Clone)]
+pub struct InputProvider {
+    pub dir: PathBuf,
+    pub base_url: String,
+    pub session: Option<String>,
+    pub min_interval: Duration,
+}
+
+impl InputProvider {
+    pub fn from_env() -> Self {
+        InputProvider {
+            dir: PathBuf::from("inputs"),
+            base_url: base_url_from_env(),
+            session: session_from_env(),
+            min_interval: Duration::from_secs(3),
+        }
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{:02}.txt", day))
+    }
+
+    pub fn input(&self, day: u8) -> Result<String> {
+        let path = self.path(day);
+        if let Ok(cached) = fs::read_to_string(&path) {
+            if !cached.is_empty() {
+                return Ok(cached);
+            }
+        }
+
+        let Some(session) = &self.session else {
+            anyhow::bail!(ParseError::NoInputFile(day));
+        };
+
+        let input = self.fetch(day, session)?;
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("could not create {}", self.dir.display()))?;
+        fs::write(&path, &input).with_context(|| format!("could not write {}", path.display()))?;
+        Ok(input)
+    }
+
+    fn fetch(&self, day: u8, session: &str) -> Result<String> {
+        self.wait_for_rate_limit();
+
+        let url = format!("{}/day/{}/input", self.base_url.trim_end_matches('/'), day);
+        let response = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", USER_AGENT)
+            .call();
+        self.mark_request()?;
+
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(status, _)) => {
+                anyhow::bail!("fetching {} failed with HTTP {}", url, status)
+            }
+            Err(e) => Err(e).with_context(|| format!("could not fetch {}", url)),
+        }
+    }
+
+    fn marker(&self) -> PathBuf {
+        self.dir.join(".last-fetch")
+    }
+
+    /// When the last request finished, as recorded in the marker in milliseconds since the epoch.
+    fn last_request(&self) -> Option<SystemTime> {
+        let millis = fs::read_to_string(self.marker())
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        Some(UNIX_EPOCH + Duration::from_millis(millis))
+    }
+
+    fn wait_for_rate_limit(&self) {
+        if let Some(last) = self.last_request() {
+            let since = SystemTime::now()
+                .duration_since(last)
+                .unwrap_or(Duration::ZERO);
+            if since < self.min_interval {
+                thread::sleep(self.min_interval - since);
+            }
+        }
+    }
+
+    fn mark_request(&self) -> Result<()> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_millis();
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.marker(), millis.to_string())
+            .with_context(|| format!("could not write {}", self.marker().display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{stub_server, temp_dir};
+
+    fn provider(name: &str, base_url: String) -> InputProvider {
+        InputProvider {
+            dir: temp_dir(name),
+            base_url,
+            session: Some("secret".to_string()),
+            min_interval: Duration::from_millis(200),
+        }
+    }
+
+    #[test]
+    fn fetches_once_and_caches() {
+        let (url, requests) = stub_server(200, "1,2,3\n");
+        let provider = provider("fetch", url);
+
+        assert_eq!(provider.input(3).unwrap(), "1,2,3\n");
+        assert_eq!(provider.input(3).unwrap(), "1,2,3\n");
+        assert_eq!(fs::read_to_string(provider.path(3)).unwrap(), "1,2,3\n");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2025/day/3/input "));
+        assert!(requests[0].contains("session=secret"));
+
+        fs::remove_dir_all(&provider.dir).unwrap();
+    }
+
+    #[test]
+    fn rate_limits_requests() {
+        let (url, requests) = stub_server(200, "x");
+        let provider = provider("ratelimit", url);
+
+        provider.input(1).unwrap();
+        let first = provider.last_request().unwrap();
+        provider.input(2).unwrap();
+        let second = provider.last_request().unwrap();
+        assert!(second.duration_since(first).unwrap() >= provider.min_interval);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        fs::remove_dir_all(&provider.dir).unwrap();
+    }
+
+    #[test]
+    fn reports_missing_session_and_http_errors() {
+        let (url, _) = stub_server(404, "not yet");
+        let mut provider = provider("errors", url);
+
+        let err = provider.input(4).unwrap_err();
+        assert!(err.to_string().contains("HTTP 404"));
+        assert!(!provider.path(4).exists());
+
+        provider.session = None;
+        let err = provider.input(4).unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError::NoInputFile(4)
+        );
+
+        let _ = fs::remove_dir_all(&provider.dir);
+    }
+}
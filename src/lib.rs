@@ -1,7 +1,29 @@
+pub mod answer;
+pub mod answers;
+pub mod beam;
+pub mod bench;
 pub mod cli;
 pub mod days;
+pub mod dsu;
+pub mod examples;
 pub mod grid;
+pub mod input;
+pub mod parse;
+pub mod range_set;
+pub mod scaffold;
+pub mod solution;
+pub mod submit;
+pub mod watch;
 
+#[cfg(test)]
+mod test_util;
+
+pub use answer::Answer;
 pub use grid::Direction;
 pub use grid::Grid;
+pub use grid::Grid3;
 pub use grid::Point;
+pub use grid::Point3;
+pub use range_set::RangeSet;
+pub use solution::Metadata;
+pub use solution::Solution;
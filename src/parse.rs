@@ -0,0 +1,293 @@
+use nom::branch::alt;
+use nom::bytes::complete::take_while1;
+use nom::character::complete::{
+    alphanumeric0, char, digit1, line_ending, multispace0, not_line_ending, one_of, space0, space1,
+};
+use nom::combinator::{cut, eof, map_res, not, opt, recognize};
+use nom::error::{context, VerboseError, VerboseErrorKind};
+use nom::multi::separated_list1;
+use nom::sequence::{pair, separated_pair, terminated, tuple};
+use nom::{Err, IResult, Parser};
+use std::str::FromStr;
+
+use crate::cli::ParseError;
+use crate::grid::Grid;
+
+/// Result of every parser in this module.
+pub type PResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// Runs `parser` over the whole of `input`, allowing only trailing whitespace after it.
+///
+/// Failures are reported at the innermost `context`, worded by the outermost `context` at that
+/// same position, so a day can reword any of these combinators by wrapping it in its own.
+pub fn run<'a, T>(
+    day: u8,
+    input: &'a str,
+    parser: impl Parser<&'a str, T, VerboseError<&'a str>>,
+) -> Result<T, ParseError> {
+    match terminated(parser, pair(multispace0, eof)).parse(input) {
+        Ok((_, value)) => Ok(value),
+        Err(Err::Error(e) | Err::Failure(e)) => Err(to_parse_error(day, input, e)),
+        Err(Err::Incomplete(_)) => Err(ParseError::at(
+            day,
+            input,
+            &input[input.len()..],
+            "unexpected end of input",
+        )),
+    }
+}
+
+fn to_parse_error(day: u8, input: &str, e: VerboseError<&str>) -> ParseError {
+    let contexts = e
+        .errors
+        .iter()
+        .filter_map(|(at, kind)| match kind {
+            VerboseErrorKind::Context(message) => Some((*at, message.to_string())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let (at, message) = match contexts.first() {
+        Some((innermost, _)) => contexts
+            .iter()
+            .rev()
+            .find(|(at, _)| at.as_ptr() == innermost.as_ptr())
+            .cloned()
+            .unwrap_or_default(),
+        None => match e.errors.first() {
+            Some((at, VerboseErrorKind::Char(c))) => (*at, format!("expected `{}`", c)),
+            Some((at, VerboseErrorKind::Nom(nom::error::ErrorKind::Eof))) => {
+                (*at, "unexpected input".to_string())
+            }
+            Some((at, VerboseErrorKind::Nom(kind))) => (*at, format!("expected {:?}", kind)),
+            _ => (&input[input.len()..], "invalid input".to_string()),
+        },
+    };
+
+    let token = at
+        .find(|c: char| c.is_whitespace() || c == ',')
+        .unwrap_or(at.len());
+    ParseError::at(day, input, &at[..token], message)
+}
+
+/// Parses `text`, a slice of `input`, reporting its position if it is not a valid `T`. For
+/// hand-rolled parsers that split lines themselves rather than using the combinators below.
+pub fn parse_at<T: FromStr>(day: u8, input: &str, text: &str) -> Result<T, ParseError> {
+    text.trim()
+        .parse()
+        .map_err(|_| ParseError::at(day, input, text, "expected a number"))
+}
+
+fn fail<'a, T>(at: &'a str, message: &'static str) -> PResult<'a, T> {
+    Err(Err::Failure(VerboseError {
+        errors: vec![(at, VerboseErrorKind::Context(message))],
+    }))
+}
+
+// ============================================================================
+// Numbers
+// ============================================================================
+
+/// Digits only. Letters stuck to the digits, as in `12x`, fail the whole token.
+pub fn unsigned<T: FromStr>(input: &str) -> PResult<'_, T> {
+    context(
+        "expected a number",
+        map_res(recognize(pair(digit1, alphanumeric0)), str::parse),
+    )(input)
+}
+
+/// Digits with an optional leading `+` or `-`.
+pub fn signed<T: FromStr>(input: &str) -> PResult<'_, T> {
+    context(
+        "expected a number",
+        map_res(
+            recognize(tuple((opt(one_of("+-")), digit1, alphanumeric0))),
+            str::parse,
+        ),
+    )(input)
+}
+
+/// An inclusive `a-b` range of unsigned numbers.
+pub fn range<T: FromStr>(input: &str) -> PResult<'_, (T, T)> {
+    context(
+        "expected a range like `a-b`",
+        separated_pair(unsigned, char('-'), cut(unsigned)),
+    )(input)
+}
+
+// ============================================================================
+// Lists
+// ============================================================================
+
+/// One or more `item`s separated by commas, which may be followed by whitespace.
+pub fn comma_separated<'a, T>(
+    item: impl Parser<&'a str, T, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(tuple((space0, char(','), multispace0)), cut(item))
+}
+
+/// One or more `item`s on a single line separated by spaces or tabs.
+pub fn whitespace_separated<'a, T>(
+    item: impl Parser<&'a str, T, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(terminated(space1, not(alt((line_ending, eof)))), cut(item))
+}
+
+/// One `item` per line. The list ends at a blank line or the end of input, and every line
+/// before that must match.
+pub fn lines<'a, T>(
+    item: impl Parser<&'a str, T, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(
+        terminated(line_ending, not(alt((line_ending, eof)))),
+        cut(item),
+    )
+}
+
+/// One or more blocks separated by blank lines, each parsed by `section`.
+pub fn sections<'a, T>(
+    section: impl Parser<&'a str, T, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(pair(line_ending, line_ending), section)
+}
+
+// ============================================================================
+// Structured lines
+// ============================================================================
+
+/// A rectangular block of lines whose cells are all listed in `allowed`.
+pub fn char_grid<'a>(allowed: &'static str) -> impl FnMut(&'a str) -> PResult<'a, Grid<char>> {
+    move |input: &'a str| {
+        let (rest, rows) = lines(not_line_ending)(input)?;
+        let width = rows[0].chars().count();
+        for row in &rows {
+            if row.chars().count() != width {
+                return fail(row, "expected rows of equal width");
+            }
+            if let Some((i, _)) = row.char_indices().find(|(_, c)| !allowed.contains(*c)) {
+                return fail(&row[i..], "unexpected cell");
+            }
+        }
+        let cells = rows
+            .iter()
+            .map(|row| row.chars().collect())
+            .collect::<Vec<Vec<char>>>();
+        Ok((rest, Grid::from(cells)))
+    }
+}
+
+/// `key: value` or `key = value`, where the key is letters, digits, `_` and `-`.
+pub fn key_value<'a, T>(
+    value: impl Parser<&'a str, T, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, (&'a str, T)> {
+    separated_pair(
+        context(
+            "expected a key",
+            take_while1(|c: char| c.is_alphanumeric() || c == '_' || c == '-'),
+        ),
+        context("expected `:` or `=`", tuple((space0, one_of(":="), space0))),
+        cut(value),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_and_ranges() {
+        assert_eq!(run(1, "42\n", unsigned::<u8>), Ok(42));
+        assert_eq!(run(1, "-42", signed::<i64>), Ok(-42));
+        assert_eq!(run(1, "3-5", range::<u64>), Ok((3, 5)));
+        assert_eq!(
+            run(1, "300", unsigned::<u8>),
+            Err(ParseError::malformed(1, 1, 1, "300", "expected a number"))
+        );
+        assert_eq!(
+            run(1, "3-5x", range::<u64>),
+            Err(ParseError::malformed(1, 1, 3, "5x", "expected a number"))
+        );
+        assert_eq!(
+            run(1, "35", range::<u64>),
+            Err(ParseError::malformed(
+                1,
+                1,
+                1,
+                "35",
+                "expected a range like `a-b`"
+            ))
+        );
+    }
+
+    #[test]
+    fn numbers_at_a_position() {
+        let input = "12\n3x4\n";
+        assert_eq!(parse_at::<i64>(9, input, &input[..2]), Ok(12));
+        assert_eq!(
+            parse_at::<i64>(9, input, &input[3..6]),
+            Err(ParseError::malformed(9, 2, 1, "3x4", "expected a number"))
+        );
+    }
+
+    #[test]
+    fn lists_and_sections() {
+        assert_eq!(
+            run(1, "1,2, 3", comma_separated(unsigned::<u32>)),
+            Ok(vec![1, 2, 3])
+        );
+        assert_eq!(
+            run(1, "1  -2\t3 ", whitespace_separated(signed::<i32>)),
+            Ok(vec![1, -2, 3])
+        );
+        assert_eq!(
+            run(1, "1\n2\n\n3\n", sections(lines(unsigned::<u32>))),
+            Ok(vec![vec![1, 2], vec![3]])
+        );
+        assert_eq!(
+            run(1, "1\n2\nx\n", lines(unsigned::<u32>)),
+            Err(ParseError::malformed(1, 3, 1, "x", "expected a number"))
+        );
+        assert_eq!(
+            run(1, "1,2,", comma_separated(unsigned::<u32>)),
+            Err(ParseError::malformed(1, 1, 5, "", "expected a number"))
+        );
+    }
+
+    #[test]
+    fn grids_and_key_values() {
+        let grid = run(1, ".@\n@.\n", char_grid(".@")).unwrap();
+        assert_eq!((grid.width, grid.height), (2, 2));
+        assert_eq!(
+            run(1, ".@\n@x\n", char_grid(".@")),
+            Err(ParseError::malformed(1, 2, 2, "x", "unexpected cell"))
+        );
+        assert_eq!(
+            run(1, ".@\n@\n", char_grid(".@")),
+            Err(ParseError::malformed(
+                1,
+                2,
+                1,
+                "@",
+                "expected rows of equal width"
+            ))
+        );
+
+        assert_eq!(
+            run(1, "a: 1\nb-c = 2", lines(key_value(unsigned::<u8>))),
+            Ok(vec![("a", 1), ("b-c", 2)])
+        );
+        assert_eq!(
+            run(1, "a 1", lines(key_value(unsigned::<u8>))),
+            Err(ParseError::malformed(1, 1, 2, "", "expected `:` or `=`"))
+        );
+    }
+
+    #[test]
+    fn callers_can_reword_errors() {
+        let parser = context("expected an ID range", range::<u64>);
+        assert_eq!(
+            run(1, "35", parser),
+            Err(ParseError::malformed(1, 1, 1, "35", "expected an ID range"))
+        );
+    }
+}
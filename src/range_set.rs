@@ -0,0 +1,210 @@
+use std::slice;
+
+/// A set of `i64`s stored as sorted, disjoint, inclusive ranges.
+///
+/// Overlapping and touching ranges are merged on insert, so `1-3` and `4-6` become `1-6`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    ranges: Vec<(i64, i64)>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        RangeSet::default()
+    }
+
+    /// Adds every value in `start..=end`. Empty ranges (`start > end`) are ignored.
+    pub fn insert(&mut self, start: i64, end: i64) {
+        if start > end {
+            return;
+        }
+        let first = self
+            .ranges
+            .partition_point(|r| r.1.saturating_add(1) < start);
+        let last = self
+            .ranges
+            .partition_point(|r| r.0 <= end.saturating_add(1));
+
+        let merged = match first < last {
+            true => (
+                start.min(self.ranges[first].0),
+                end.max(self.ranges[last - 1].1),
+            ),
+            false => (start, end),
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Removes every value in `start..=end`, splitting any range that straddles it.
+    pub fn remove(&mut self, start: i64, end: i64) {
+        if start > end {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.1 < start);
+        let last = self.ranges.partition_point(|r| r.0 <= end);
+        if first == last {
+            return;
+        }
+
+        let (left, right) = (self.ranges[first], self.ranges[last - 1]);
+        let mut kept = vec![];
+        if left.0 < start {
+            kept.push((left.0, start - 1));
+        }
+        if right.1 > end {
+            kept.push((end + 1, right.1));
+        }
+        self.ranges.splice(first..last, kept);
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let i = self.ranges.partition_point(|r| r.1 < value);
+        self.ranges.get(i).is_some_and(|r| r.0 <= value)
+    }
+
+    /// How many values the set holds, which for the full `i64` range is one more than `u64`
+    /// can count.
+    pub fn covered(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|(s, e)| e.abs_diff(*s) as u128 + 1)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The merged ranges in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.ranges.iter().copied()
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut union = self.clone();
+        for (start, end) in other {
+            union.insert(start, end);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        let mut ranges = vec![];
+        while let (Some(&&x), Some(&&y)) = (a.peek(), b.peek()) {
+            let (start, end) = (x.0.max(y.0), x.1.min(y.1));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            match x.1 < y.1 {
+                true => a.next(),
+                false => b.next(),
+            };
+        }
+        RangeSet { ranges }
+    }
+
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut difference = self.clone();
+        for (start, end) in other {
+            difference.remove(start, end);
+        }
+        difference
+    }
+
+    /// The values in `start..=end` that are not in the set.
+    pub fn complement(&self, start: i64, end: i64) -> RangeSet {
+        RangeSet::from_iter([(start, end)]).difference(self)
+    }
+}
+
+impl FromIterator<(i64, i64)> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = (i64, i64)>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        for (start, end) in iter {
+            set.insert(start, end);
+        }
+        set
+    }
+}
+
+impl<'a> IntoIterator for &'a RangeSet {
+    type Item = (i64, i64);
+    type IntoIter = std::iter::Copied<slice::Iter<'a, (i64, i64)>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(i64, i64)]) -> RangeSet {
+        ranges.iter().copied().collect()
+    }
+
+    fn ranges(set: &RangeSet) -> Vec<(i64, i64)> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_touching() {
+        let s = set(&[(10, 14), (3, 5), (16, 20), (12, 18)]);
+        assert_eq!(ranges(&s), vec![(3, 5), (10, 20)]);
+        assert_eq!(s.covered(), 14);
+
+        let s = set(&[(1, 3), (4, 6), (9, 9), (8, 7)]);
+        assert_eq!(ranges(&s), vec![(1, 6), (9, 9)]);
+
+        let s = set(&[(i64::MIN, 0), (1, i64::MAX)]);
+        assert_eq!(ranges(&s), vec![(i64::MIN, i64::MAX)]);
+        assert_eq!(s.covered(), 1 << 64);
+        assert!(RangeSet::new().is_empty());
+    }
+
+    #[test]
+    fn remove_splits_ranges() {
+        let mut s = set(&[(1, 10), (20, 30)]);
+        s.remove(5, 6);
+        assert_eq!(ranges(&s), vec![(1, 4), (7, 10), (20, 30)]);
+        s.remove(8, 25);
+        assert_eq!(ranges(&s), vec![(1, 4), (7, 7), (26, 30)]);
+        s.remove(0, 100);
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn membership() {
+        let s = set(&[(3, 5), (10, 20)]);
+        let members = (0..=21).filter(|&x| s.contains(x)).count();
+        assert_eq!(members as u128, s.covered());
+        assert!(s.contains(3) && s.contains(5) && s.contains(10) && s.contains(20));
+        assert!(!s.contains(2) && !s.contains(6) && !s.contains(9) && !s.contains(21));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(1, 5), (10, 15)]);
+        let b = set(&[(4, 11), (20, 25)]);
+        assert_eq!(ranges(&a.union(&b)), vec![(1, 15), (20, 25)]);
+        assert_eq!(ranges(&a.intersection(&b)), vec![(4, 5), (10, 11)]);
+        assert_eq!(ranges(&a.difference(&b)), vec![(1, 3), (12, 15)]);
+        assert_eq!(ranges(&a.complement(0, 20)), vec![(0, 0), (6, 9), (16, 20)]);
+
+        for x in -1..=26 {
+            assert_eq!(
+                a.intersection(&b).contains(x),
+                a.contains(x) && b.contains(x)
+            );
+            assert_eq!(
+                a.difference(&b).contains(x),
+                a.contains(x) && !b.contains(x)
+            );
+        }
+    }
+}
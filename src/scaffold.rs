@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = r#"use crate::answer::Answer;
+use crate::solution::{Metadata, Solution};
+use anyhow::Result;
+
+const DAY: u8 = {day};
+
+pub struct Day{day:02};
+
+impl Solution for Day{day:02} {
+    type Parsed = Vec<String>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: DAY,
+            title: "{title}",
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, lines: &Vec<String>) -> Result<Answer> {
+        Ok(part_one(lines)?.into())
+    }
+
+    fn part_two(&self, lines: &Vec<String>) -> Result<Answer> {
+        Ok(part_two(lines)?.into())
+    }
+}
+
+pub fn part_one(_lines: &[String]) -> Result<i64> {
+    Ok(0)
+}
+
+pub fn part_two(_lines: &[String]) -> Result<i64> {
+    Ok(0)
+}
+
+fn parse_input(input: &str) -> Result<Vec<String>> {
+    Ok(input.lines().map(str::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = include_str!("../../inputs/examples/day{day:02}-1.txt");
+
+    #[test]
+    #[ignore]
+    fn test_part_one_example() {
+        let result = part_one(&parse_input(INPUT).unwrap()).unwrap();
+        assert_eq!(result, 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_part_two_example() {
+        let result = part_two(&parse_input(INPUT).unwrap()).unwrap();
+        assert_eq!(result, 0);
+    }
+}
+"#;
+
+/// Source of a fresh `dayNN.rs` module.
+pub fn render(day: u8, title: &str) -> String {
+    TEMPLATE
+        .replace("{day:02}", &format!("{:02}", day))
+        .replace("{day}", &day.to_string())
+        .replace("{title}", &title.replace('"', "\\\""))
+}
+
+/// Adds `pub mod dayNN;` and `&dayNN::DayNN,` to the contents of `days/mod.rs`, keeping both
+/// lists sorted.
+pub fn register(mod_rs: &str, day: u8) -> Result<String> {
+    let module = format!("pub mod day{:02};", day);
+    let entry = format!("    &day{:02}::Day{:02},", day, day);
+
+    let mut lines = mod_rs.lines().map(str::to_string).collect::<Vec<_>>();
+    insert_sorted(&mut lines, &module, "pub mod day").context("no `pub mod dayNN;` lines found")?;
+    insert_sorted(&mut lines, &entry, "    &day").context("no SOLUTIONS registry found")?;
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Inserts `line` among the consecutive lines starting with `prefix`, in sorted order.
+fn insert_sorted(lines: &mut Vec<String>, line: &str, prefix: &str) -> Option<()> {
+    let first = lines.iter().position(|l| l.starts_with(prefix))?;
+    let last = first
+        + lines[first..]
+            .iter()
+            .take_while(|l| l.starts_with(prefix))
+            .count();
+
+    let at = (first..last)
+        .find(|&i| lines[i].as_str() > line)
+        .unwrap_or(last);
+    lines.insert(at, line.to_string());
+    Some(())
+}
+
+/// Creates `src/days/dayNN.rs`, registers it and creates an empty `inputs/dayNN.txt`, plus an
+/// empty first example `inputs/examples/dayNN-1.txt` with no answers yet in `dayNN-1.json`.
+/// Refuses to touch anything if the module already exists. Returns the files created or changed.
+pub fn create(root: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>> {
+    let days_dir = root.join("src").join("days");
+    let mod_rs = days_dir.join("mod.rs");
+    let module = days_dir.join(format!("day{:02}.rs", day));
+    let inputs = root.join("inputs");
+    let examples = inputs.join("examples");
+
+    if module.exists() {
+        anyhow::bail!("{} already exists", module.display());
+    }
+    let registry = fs::read_to_string(&mod_rs).with_context(|| {
+        format!(
+            "could not read {}, run this from the repository root",
+            mod_rs.display()
+        )
+    })?;
+    let registry = register(&registry, day)?;
+
+    fs::write(&module, render(day, title))
+        .with_context(|| format!("could not write {}", module.display()))?;
+    fs::write(&mod_rs, registry)
+        .with_context(|| format!("could not write {}", mod_rs.display()))?;
+
+    let mut changed = vec![module, mod_rs];
+    fs::create_dir_all(&examples)?;
+    for (path, contents) in [
+        (inputs.join(format!("day{:02}.txt", day)), ""),
+        (examples.join(format!("day{:02}-1.txt", day)), ""),
+        (examples.join(format!("day{:02}-1.json", day)), "{}\n"),
+    ] {
+        if !path.exists() {
+            fs::write(&path, contents)
+                .with_context(|| format!("could not write {}", path.display()))?;
+            changed.push(path);
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::Examples;
+    use crate::solution::Part;
+    use crate::test_util::temp_dir;
+
+    const MOD_RS: &str = "use crate::solution::DynSolution;
+
+pub mod day01;
+pub mod day03;
+
+pub static SOLUTIONS: &[&dyn DynSolution] = &[
+    &day01::Day01,
+    &day03::Day03,
+];
+";
+
+    #[test]
+    fn render_template() {
+        let source = render(9, "Some \"Title\"");
+        assert!(source.contains("const DAY: u8 = 9;"));
+        assert!(source.contains("pub struct Day09;"));
+        assert!(source.contains("impl Solution for Day09 {"));
+        assert!(source.contains(r#"title: "Some \"Title\"","#));
+        assert!(source.contains(r#"include_str!("../../inputs/examples/day09-1.txt")"#));
+    }
+
+    #[test]
+    fn register_keeps_order() {
+        let registry = register(MOD_RS, 2).unwrap();
+        assert!(registry.contains("pub mod day01;\npub mod day02;\npub mod day03;\n"));
+        assert!(registry.contains("    &day01::Day01,\n    &day02::Day02,\n    &day03::Day03,\n"));
+
+        let registry = register(MOD_RS, 12).unwrap();
+        assert!(registry.contains("pub mod day03;\npub mod day12;\n"));
+        assert!(registry.contains("    &day03::Day03,\n    &day12::Day12,\n];"));
+
+        assert!(register("", 2).is_err());
+    }
+
+    #[test]
+    fn create_refuses_to_overwrite() {
+        let root = temp_dir("scaffold");
+        fs::create_dir_all(root.join("src/days")).unwrap();
+        fs::write(root.join("src/days/mod.rs"), MOD_RS).unwrap();
+
+        let created = create(&root, 2, "Test").unwrap();
+        assert_eq!(created.len(), 5);
+        assert!(root.join("src/days/day02.rs").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("inputs/day02.txt")).unwrap(),
+            ""
+        );
+        let examples = Examples {
+            dir: root.join("inputs/examples"),
+        };
+        assert_eq!(examples.list(2).unwrap(), vec![1]);
+        assert_eq!(examples.answers(1).get(2, Part::One).unwrap(), None);
+
+        let err = create(&root, 2, "Test").unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
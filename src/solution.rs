@@ -0,0 +1,199 @@
+use anyhow::Result;
+use std::fmt::{self, Display};
+use std::hint::black_box;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::answer::Answer;
+
+/// Static information about a puzzle day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    pub day: u8,
+    pub title: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim() {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("invalid part: {:?} (expected 1 or 2)", s)),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// The rendered answer of one part and how long it took to compute.
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: Part,
+    pub answer: Answer,
+    pub elapsed: Duration,
+}
+
+/// Everything produced by running a day against one input.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub day: u8,
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+/// Raw timing samples collected by [`DynSolution::bench`].
+#[derive(Debug, Clone)]
+pub struct Samples {
+    pub parse: Vec<Duration>,
+    pub parts: Vec<(Part, Vec<Duration>)>,
+}
+
+/// A single day's puzzle. `parse` runs once and both parts share its output.
+pub trait Solution {
+    type Parsed;
+
+    fn metadata(&self) -> Metadata;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed>;
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<Answer>;
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<Answer>;
+
+    fn run_part(&self, parsed: &Self::Parsed, part: Part) -> Result<Answer> {
+        match part {
+            Part::One => self.part_one(parsed),
+            Part::Two => self.part_two(parsed),
+        }
+    }
+}
+
+/// Object safe view of a [`Solution`], so every day can live in the same registry.
+pub trait DynSolution: Sync {
+    fn metadata(&self) -> Metadata;
+
+    /// Parses the input once, then runs and times the requested parts.
+    fn solve(&self, input: &str, parts: &[Part]) -> Result<Report>;
+
+    /// Times `parse` and each requested part separately, `iterations` times each after
+    /// `warmup` untimed runs.
+    fn bench(
+        &self,
+        input: &str,
+        parts: &[Part],
+        warmup: usize,
+        iterations: usize,
+    ) -> Result<Samples>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+{
+    fn metadata(&self) -> Metadata {
+        Solution::metadata(self)
+    }
+
+    fn solve(&self, input: &str, parts: &[Part]) -> Result<Report> {
+        let start = Instant::now();
+        let parsed = self.parse(input)?;
+        let parse_elapsed = start.elapsed();
+
+        let parts = parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let answer = self.run_part(&parsed, part)?;
+                Ok(PartResult {
+                    part,
+                    answer,
+                    elapsed: start.elapsed(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Report {
+            day: Solution::metadata(self).day,
+            parse_elapsed,
+            parts,
+        })
+    }
+
+    fn bench(
+        &self,
+        input: &str,
+        parts: &[Part],
+        warmup: usize,
+        iterations: usize,
+    ) -> Result<Samples> {
+        // Fail before timing anything if the input or a part is broken.
+        let parsed = self.parse(input)?;
+        for &part in parts {
+            self.run_part(&parsed, part)?;
+        }
+
+        let parse = sample(warmup, iterations, || {
+            let _ = black_box(self.parse(black_box(input)));
+        });
+
+        let parts = parts
+            .iter()
+            .map(|&part| {
+                let samples = sample(warmup, iterations, || {
+                    let _ = black_box(self.run_part(black_box(&parsed), part));
+                });
+                (part, samples)
+            })
+            .collect();
+
+        Ok(Samples { parse, parts })
+    }
+}
+
+fn sample<F: FnMut()>(warmup: usize, iterations: usize, mut f: F) -> Vec<Duration> {
+    for _ in 0..warmup {
+        f();
+    }
+    (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_part() {
+        assert_eq!("1".parse::<Part>(), Ok(Part::One));
+        assert_eq!("2".parse::<Part>(), Ok(Part::Two));
+        assert!("3".parse::<Part>().is_err());
+    }
+}
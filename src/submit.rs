@@ -0,0 +1,376 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::input::USER_AGENT;
+use crate::solution::Part;
+
+/// How the site judged a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after a previous attempt, with the wait time if the site gave one.
+    RateLimited(Option<String>),
+    /// The part was already solved, or is not unlocked yet.
+    WrongLevel,
+    Unknown,
+}
+
+impl Outcome {
+    /// Classifies the HTML the site returns after posting an answer.
+    pub fn from_response(body: &str) -> Self {
+        if body.contains("That's the right answer") {
+            Outcome::Correct
+        } else if body.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if body.contains("your answer is too low") {
+            Outcome::TooLow
+        } else if body.contains("That's not the right answer") {
+            Outcome::Incorrect
+        } else if body.contains("You gave an answer too recently") {
+            let wait = body
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            Outcome::RateLimited(wait)
+        } else if body.contains("You don't seem to be solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::Incorrect => "incorrect",
+            Outcome::TooHigh => "too_high",
+            Outcome::TooLow => "too_low",
+            Outcome::RateLimited(_) => "rate_limited",
+            Outcome::WrongLevel => "wrong_level",
+            Outcome::Unknown => "unknown",
+        }
+    }
+
+    fn from_key(key: &str) -> Self {
+        match key {
+            "correct" => Outcome::Correct,
+            "incorrect" => Outcome::Incorrect,
+            "too_high" => Outcome::TooHigh,
+            "too_low" => Outcome::TooLow,
+            "rate_limited" => Outcome::RateLimited(None),
+            "wrong_level" => Outcome::WrongLevel,
+            _ => Outcome::Unknown,
+        }
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Incorrect | Outcome::TooHigh | Outcome::TooLow
+        )
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::Incorrect => write!(f, "incorrect"),
+            Outcome::TooHigh => write!(f, "incorrect, too high"),
+            Outcome::TooLow => write!(f, "incorrect, too low"),
+            Outcome::RateLimited(Some(wait)) => write!(f, "rate limited, {} left to wait", wait),
+            Outcome::RateLimited(None) => write!(f, "rate limited"),
+            Outcome::WrongLevel => write!(f, "part already solved or not unlocked yet"),
+            Outcome::Unknown => write!(f, "unrecognised response"),
+        }
+    }
+}
+
+/// One submission, as stored in the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub outcome: Outcome,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl Attempt {
+    fn to_json(&self) -> Value {
+        json!({
+            "day": self.day,
+            "part": self.part.number(),
+            "answer": self.answer,
+            "outcome": self.outcome.key(),
+            "timestamp": self.timestamp,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(Attempt {
+            day: value["day"].as_u64()? as u8,
+            part: value["part"].as_u64()?.to_string().parse().ok()?,
+            answer: value["answer"].as_str()?.to_string(),
+            outcome: Outcome::from_key(value["outcome"].as_str()?),
+            timestamp: value["timestamp"].as_u64()?,
+        })
+    }
+}
+
+/// Every attempt ever made, one JSON object per line.
+#[derive(Debug, Clone)]
+pub struct SubmissionLog {
+    path: PathBuf,
+}
+
+impl Default for SubmissionLog {
+    fn default() -> Self {
+        SubmissionLog::new("answers/submissions.jsonl")
+    }
+}
+
+impl SubmissionLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        SubmissionLog { path: path.into() }
+    }
+
+    pub fn attempts(&self) -> Result<Vec<Attempt>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let text = fs::read_to_string(&self.path)
+            .with_context(|| format!("could not read {}", self.path.display()))?;
+
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .ok()
+                    .and_then(|value| Attempt::from_json(&value))
+                    .with_context(|| {
+                        format!("{}:{}: malformed attempt", self.path.display(), i + 1)
+                    })
+            })
+            .collect()
+    }
+
+    pub fn append(&self, attempt: &Attempt) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("could not open {}", self.path.display()))?;
+        writeln!(file, "{}", attempt.to_json())?;
+        Ok(())
+    }
+
+    /// Explains why `answer` should not be submitted, based on earlier attempts, if it shouldn't.
+    pub fn refusal(&self, day: u8, part: Part, answer: &str) -> Result<Option<String>> {
+        let attempts = self.attempts()?;
+        let previous = attempts
+            .iter()
+            .filter(|a| a.day == day && a.part == part)
+            .collect::<Vec<_>>();
+
+        if let Some(correct) = previous.iter().find(|a| a.outcome == Outcome::Correct) {
+            return Ok(Some(format!("already solved with {}", correct.answer)));
+        }
+        if let Some(wrong) = previous
+            .iter()
+            .find(|a| a.answer == answer && a.outcome.is_wrong())
+        {
+            return Ok(Some(format!(
+                "{} was already submitted: {}",
+                answer, wrong.outcome
+            )));
+        }
+
+        let Ok(value) = answer.parse::<i128>() else {
+            return Ok(None);
+        };
+        let bound = |outcome: Outcome| {
+            previous
+                .iter()
+                .filter(move |a| a.outcome == outcome)
+                .filter_map(|a| a.answer.parse::<i128>().ok())
+        };
+        if let Some(high) = bound(Outcome::TooHigh).min().filter(|&high| value >= high) {
+            return Ok(Some(format!("{} is too high, {} already was", value, high)));
+        }
+        if let Some(low) = bound(Outcome::TooLow).max().filter(|&low| value <= low) {
+            return Ok(Some(format!("{} is too low, {} already was", value, low)));
+        }
+        Ok(None)
+    }
+}
+
+/// Posts answers to `{base_url}/day/N/answer` and logs every attempt.
+#[derive(Debug, Clone)]
+pub struct Submitter {
+    pub base_url: String,
+    pub session: String,
+    pub log: SubmissionLog,
+}
+
+impl Submitter {
+    /// Submits `answer` unless the log shows it cannot be right. Refusals are errors.
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Outcome> {
+        if let Some(reason) = self.log.refusal(day, part, answer)? {
+            anyhow::bail!("refusing to submit: {}", reason);
+        }
+
+        let url = format!("{}/day/{}/answer", self.base_url.trim_end_matches('/'), day);
+        let response = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+
+        let body = match response {
+            Ok(response) => response.into_string()?,
+            Err(ureq::Error::Status(status, _)) => {
+                anyhow::bail!("submitting to {} failed with HTTP {}", url, status)
+            }
+            Err(e) => return Err(e).with_context(|| format!("could not submit to {}", url)),
+        };
+
+        let outcome = Outcome::from_response(&body);
+        self.log.append(&Attempt {
+            day,
+            part,
+            answer: answer.to_string(),
+            outcome: outcome.clone(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        })?;
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{stub_server, temp_dir};
+
+    fn temp_log(name: &str) -> SubmissionLog {
+        SubmissionLog::new(temp_dir(name).join("submissions.jsonl"))
+    }
+
+    fn attempt(answer: &str, outcome: Outcome) -> Attempt {
+        Attempt {
+            day: 1,
+            part: Part::One,
+            answer: answer.to_string(),
+            outcome,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn parse_responses() {
+        let page = |s: &str| format!("<main><article><p>{}</p></article></main>", s);
+        assert_eq!(
+            Outcome::from_response(&page(
+                "That's the right answer! You are one gold star closer."
+            )),
+            Outcome::Correct
+        );
+        assert_eq!(
+            Outcome::from_response(&page(
+                "That's not the right answer; your answer is too high."
+            )),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            Outcome::from_response(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            Outcome::from_response(&page("That's not the right answer.")),
+            Outcome::Incorrect
+        );
+        assert_eq!(
+            Outcome::from_response(&page(
+                "You gave an answer too recently. You have 39s left to wait."
+            )),
+            Outcome::RateLimited(Some("39s".to_string()))
+        );
+        assert_eq!(
+            Outcome::from_response(&page("You don't seem to be solving the right level.")),
+            Outcome::WrongLevel
+        );
+        assert_eq!(Outcome::from_response("???"), Outcome::Unknown);
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers() {
+        let log = temp_log("refusal");
+        log.append(&attempt("100", Outcome::TooHigh)).unwrap();
+        log.append(&attempt("10", Outcome::TooLow)).unwrap();
+        log.append(&attempt("50", Outcome::Incorrect)).unwrap();
+        log.append(&attempt("60", Outcome::RateLimited(None)))
+            .unwrap();
+
+        assert!(log.refusal(1, Part::One, "50").unwrap().is_some());
+        assert!(log.refusal(1, Part::One, "100").unwrap().is_some());
+        assert!(log.refusal(1, Part::One, "150").unwrap().is_some());
+        assert!(log.refusal(1, Part::One, "5").unwrap().is_some());
+        assert_eq!(log.refusal(1, Part::One, "60").unwrap(), None);
+        assert_eq!(log.refusal(1, Part::One, "abc").unwrap(), None);
+        assert_eq!(log.refusal(1, Part::Two, "50").unwrap(), None);
+
+        log.append(&attempt("42", Outcome::Correct)).unwrap();
+        assert!(log.refusal(1, Part::One, "60").unwrap().is_some());
+        assert_eq!(log.attempts().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn submits_and_logs() {
+        let (url, requests) = stub_server(
+            200,
+            "<p>That's not the right answer; your answer is too low.</p>",
+        );
+        let submitter = Submitter {
+            base_url: url,
+            session: "secret".to_string(),
+            log: temp_log("submit"),
+        };
+
+        assert_eq!(
+            submitter.submit(1, Part::Two, "123").unwrap(),
+            Outcome::TooLow
+        );
+        let err = submitter.submit(1, Part::Two, "120").unwrap_err();
+        assert!(err.to_string().contains("refusing"));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("POST /2025/day/1/answer "));
+        assert!(requests[0].contains("session=secret"));
+        assert!(requests[0].ends_with("level=2&answer=123"));
+
+        let attempts = submitter.log.attempts().unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].outcome, Outcome::TooLow);
+    }
+}
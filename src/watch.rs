@@ -0,0 +1,180 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Answers from one run keyed by part number, or by `error` when the day failed.
+pub type Answers = BTreeMap<String, String>;
+
+/// Re-runs a day whenever its module or its input changes.
+///
+/// The solution is rebuilt through `cargo`, so edits to the module are picked up: each round runs
+/// the day's example tests, then the real input, and prints how the answers moved.
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    pub day: u8,
+    pub interval: Duration,
+}
+
+impl Watcher {
+    pub fn paths(&self) -> Vec<PathBuf> {
+        vec![
+            PathBuf::from(format!("src/days/day{:02}.rs", self.day)),
+            PathBuf::from(format!("inputs/day{:02}.txt", self.day)),
+        ]
+    }
+
+    fn mtimes(&self) -> Vec<Option<SystemTime>> {
+        self.paths()
+            .iter()
+            .map(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+
+    pub fn run(&self) -> Result<()> {
+        for path in self.paths() {
+            println!("  watching {}", path.display());
+        }
+
+        let mut previous = None;
+        let mut seen = self.mtimes();
+        loop {
+            previous = Some(self.round(previous.as_ref())?);
+
+            loop {
+                thread::sleep(self.interval);
+                let current = self.mtimes();
+                if current != seen {
+                    seen = current;
+                    break;
+                }
+            }
+        }
+    }
+
+    fn round(&self, previous: Option<&Answers>) -> Result<Answers> {
+        println!();
+        println!("Day {:02}", self.day);
+        println!();
+
+        let tests = Command::new("cargo")
+            .args(["test", "--quiet", "--lib"])
+            .arg(format!("days::day{:02}::", self.day))
+            .output()
+            .context("could not run cargo test")?;
+        if tests.status.success() {
+            println!("  examples: ok");
+        } else {
+            println!("  examples: FAILED");
+            print!("{}", String::from_utf8_lossy(&tests.stdout));
+            print!("{}", String::from_utf8_lossy(&tests.stderr));
+        }
+
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--", "--format", "json", "--day"])
+            .arg(self.day.to_string())
+            .output()
+            .context("could not run cargo run")?;
+        let current = match output.status.success() {
+            true => parse_answers(&String::from_utf8_lossy(&output.stdout))?,
+            false => Answers::from([(
+                "error".to_string(),
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            )]),
+        };
+
+        for line in diff(previous, &current) {
+            println!("  {}", line);
+        }
+        Ok(current)
+    }
+}
+
+/// Reads the output of `--format json` into [`Answers`].
+pub fn parse_answers(json: &str) -> Result<Answers> {
+    let results: Value = serde_json::from_str(json).context("runner printed invalid JSON")?;
+    let results = results.as_array().context("runner output is not a list")?;
+
+    Ok(results
+        .iter()
+        .map(|r| match (r["part"].as_u64(), r["error"].as_str()) {
+            (_, Some(error)) => ("error".to_string(), error.to_string()),
+            (part, None) => (
+                format!("part {}", part.unwrap_or(0)),
+                r["answer"].as_str().unwrap_or_default().to_string(),
+            ),
+        })
+        .collect())
+}
+
+/// Describes each answer relative to the previous run.
+pub fn diff(previous: Option<&Answers>, current: &Answers) -> Vec<String> {
+    current
+        .iter()
+        .map(|(key, answer)| match previous.and_then(|p| p.get(key)) {
+            None if previous.is_none() => format!("{}: {}", key, answer),
+            None => format!("{}: {} (new)", key, answer),
+            Some(old) if old == answer => format!("{}: {} (unchanged)", key, answer),
+            Some(old) => format!("{}: {} -> {}", key, old, answer),
+        })
+        .chain(previous.into_iter().flat_map(|p| {
+            p.keys()
+                .filter(|key| !current.contains_key(*key))
+                .map(|key| format!("{}: gone", key))
+        }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(pairs: &[(&str, &str)]) -> Answers {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_runner_json() {
+        let json = r#"[
+            {"day": 1, "part": 1, "answer": "3", "error": null},
+            {"day": 1, "part": 2, "answer": "6", "error": null}
+        ]"#;
+        assert_eq!(
+            parse_answers(json).unwrap(),
+            answers(&[("part 1", "3"), ("part 2", "6")])
+        );
+
+        let json =
+            r#"[{"day": 1, "part": null, "answer": null, "error": "No input file found: 1"}]"#;
+        assert_eq!(
+            parse_answers(json).unwrap(),
+            answers(&[("error", "No input file found: 1")])
+        );
+    }
+
+    #[test]
+    fn diff_answers() {
+        let first = answers(&[("part 1", "3")]);
+        assert_eq!(diff(None, &first), vec!["part 1: 3"]);
+
+        let second = answers(&[("part 1", "4"), ("part 2", "6")]);
+        assert_eq!(
+            diff(Some(&first), &second),
+            vec!["part 1: 3 -> 4", "part 2: 6 (new)"]
+        );
+
+        let third = answers(&[("error", "boom")]);
+        assert_eq!(
+            diff(Some(&second), &third),
+            vec!["error: boom (new)", "part 1: gone", "part 2: gone"]
+        );
+        assert_eq!(diff(Some(&third), &third), vec!["error: boom (unchanged)"]);
+    }
+}